@@ -0,0 +1 @@
+ALTER TABLE publication ADD COLUMN language VARCHAR NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE publication ADD COLUMN series VARCHAR NULL;
+ALTER TABLE publication ADD COLUMN issue VARCHAR NULL;
@@ -0,0 +1,24 @@
+CREATE TABLE publication_backup (
+  id INTEGER NOT NULL PRIMARY KEY,
+  isbn VARCHAR NOT NULL,
+  title VARCHAR NOT NULL,
+  media_type_id INTEGER NOT NULL,
+  media_format TEXT CHECK(media_format IN ('cbr', 'cbz', 'epub', 'pdf')) NOT NULL,
+  author_id INTEGER NOT NULL,
+  thumbnail VARCHAR NULL,
+  file VARCHAR NOT NULL,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
+  language VARCHAR NULL,
+  series VARCHAR NULL,
+  issue VARCHAR NULL,
+  FOREIGN KEY(media_type_id) REFERENCES media_type(id),
+  FOREIGN KEY(author_id) REFERENCES author(id)
+);
+
+INSERT INTO publication_backup
+  SELECT id, isbn, title, media_type_id, media_format, author_id, thumbnail, file, timestamp,
+    language, series, issue
+  FROM publication;
+
+DROP TABLE publication;
+ALTER TABLE publication_backup RENAME TO publication;
@@ -0,0 +1,13 @@
+DROP TRIGGER publication_search_publication_insert;
+DROP TRIGGER publication_search_publication_update;
+DROP TRIGGER publication_search_publication_delete;
+DROP TRIGGER publication_search_author_update;
+DROP TRIGGER publication_search_tag_update;
+DROP TRIGGER publication_search_tag_delete;
+DROP TRIGGER publication_search_category_update;
+DROP TRIGGER publication_search_category_delete;
+DROP TRIGGER publication_search_publication_tag_insert;
+DROP TRIGGER publication_search_publication_tag_delete;
+DROP TRIGGER publication_search_publication_category_insert;
+DROP TRIGGER publication_search_publication_category_delete;
+DROP TABLE publication_search;
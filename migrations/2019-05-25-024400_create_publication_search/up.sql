@@ -0,0 +1,165 @@
+CREATE VIRTUAL TABLE publication_search USING fts5(
+  publication_id UNINDEXED,
+  title,
+  author,
+  tags,
+  categories,
+  tokenize = 'unicode61 remove_diacritics 1',
+  prefix = '2 3'
+);
+
+INSERT INTO publication_search (publication_id, title, author, tags, categories)
+  SELECT p.id, p.title, COALESCE(a.name, ''),
+    COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+      INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+    COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+      INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+  FROM publication p LEFT JOIN author a ON a.id = p.author_id;
+
+CREATE TRIGGER publication_search_publication_insert AFTER INSERT ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.id;
+END;
+
+CREATE TRIGGER publication_search_publication_update AFTER UPDATE ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.id;
+END;
+
+CREATE TRIGGER publication_search_publication_delete AFTER DELETE ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = OLD.id;
+END;
+
+CREATE TRIGGER publication_search_author_update AFTER UPDATE OF name ON author
+BEGIN
+  DELETE FROM publication_search WHERE publication_id IN (SELECT id FROM publication WHERE author_id = NEW.id);
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id IN (SELECT id FROM publication WHERE author_id = NEW.id);
+END;
+
+CREATE TRIGGER publication_search_tag_update AFTER UPDATE OF name ON tag
+BEGIN
+  DELETE FROM publication_search WHERE publication_id IN (SELECT publication_id FROM publication_tag WHERE tag_id = NEW.id);
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id IN (SELECT publication_id FROM publication_tag WHERE tag_id = NEW.id);
+END;
+
+CREATE TRIGGER publication_search_tag_delete AFTER DELETE ON tag
+BEGIN
+  DELETE FROM publication_search WHERE publication_id IN (SELECT publication_id FROM publication_tag WHERE tag_id = OLD.id);
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id IN (SELECT publication_id FROM publication_tag WHERE tag_id = OLD.id);
+END;
+
+CREATE TRIGGER publication_search_category_update AFTER UPDATE OF name ON category
+BEGIN
+  DELETE FROM publication_search WHERE publication_id IN (SELECT publication_id FROM publication_category WHERE category_id = NEW.id);
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id IN (SELECT publication_id FROM publication_category WHERE category_id = NEW.id);
+END;
+
+CREATE TRIGGER publication_search_category_delete AFTER DELETE ON category
+BEGIN
+  DELETE FROM publication_search WHERE publication_id IN (SELECT publication_id FROM publication_category WHERE category_id = OLD.id);
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id IN (SELECT publication_id FROM publication_category WHERE category_id = OLD.id);
+END;
+
+CREATE TRIGGER publication_search_publication_tag_insert AFTER INSERT ON publication_tag
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.publication_id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.publication_id;
+END;
+
+CREATE TRIGGER publication_search_publication_tag_delete AFTER DELETE ON publication_tag
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = OLD.publication_id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = OLD.publication_id;
+END;
+
+CREATE TRIGGER publication_search_publication_category_insert AFTER INSERT ON publication_category
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.publication_id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.publication_id;
+END;
+
+CREATE TRIGGER publication_search_publication_category_delete AFTER DELETE ON publication_category
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = OLD.publication_id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = OLD.publication_id;
+END;
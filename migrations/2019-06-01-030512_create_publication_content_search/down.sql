@@ -0,0 +1,2 @@
+DROP TRIGGER publication_content_search_publication_delete;
+DROP TABLE publication_content_search;
@@ -0,0 +1,11 @@
+CREATE VIRTUAL TABLE publication_content_search USING fts5(
+  publication_id UNINDEXED,
+  page UNINDEXED,
+  content,
+  tokenize = 'unicode61 remove_diacritics 1'
+);
+
+CREATE TRIGGER publication_content_search_publication_delete AFTER DELETE ON publication
+BEGIN
+  DELETE FROM publication_content_search WHERE publication_id = OLD.id;
+END;
@@ -0,0 +1,10 @@
+DROP TABLE session;
+DROP INDEX user_username;
+
+CREATE TABLE user_backup (
+  id INTEGER NOT NULL PRIMARY KEY,
+  username VARCHAR NOT NULL
+);
+INSERT INTO user_backup SELECT id, username FROM user;
+DROP TABLE user;
+ALTER TABLE user_backup RENAME TO user;
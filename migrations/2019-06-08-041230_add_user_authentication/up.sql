@@ -0,0 +1,10 @@
+ALTER TABLE user ADD COLUMN password_hash VARCHAR NOT NULL DEFAULT '';
+ALTER TABLE user ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT 0;
+CREATE UNIQUE INDEX user_username ON user (username);
+
+CREATE TABLE session (
+  token VARCHAR NOT NULL PRIMARY KEY,
+  user_id INTEGER NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  FOREIGN KEY(user_id) REFERENCES user(id)
+);
@@ -0,0 +1,27 @@
+CREATE TABLE publication_progress_backup (
+  publication_id INT NOT NULL PRIMARY KEY,
+  progress FLOAT NOT NULL,
+  FOREIGN KEY(publication_id) REFERENCES publication(id)
+);
+INSERT INTO publication_progress_backup
+SELECT publication_id, MAX(progress) FROM publication_progress GROUP BY publication_id;
+DROP TABLE publication_progress;
+ALTER TABLE publication_progress_backup RENAME TO publication_progress;
+
+CREATE TABLE recent_publication_backup (
+  publication_id INT NOT NULL PRIMARY KEY,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(publication_id) REFERENCES publication(id)
+);
+INSERT INTO recent_publication_backup
+SELECT publication_id, MAX(timestamp) FROM recent_publication GROUP BY publication_id;
+DROP TABLE recent_publication;
+ALTER TABLE recent_publication_backup RENAME TO recent_publication;
+
+CREATE TABLE favorite_category_backup (
+  category_id INT NOT NULL PRIMARY KEY,
+  FOREIGN KEY(category_id) REFERENCES category(id)
+);
+INSERT INTO favorite_category_backup SELECT DISTINCT category_id FROM favorite_category;
+DROP TABLE favorite_category;
+ALTER TABLE favorite_category_backup RENAME TO favorite_category;
@@ -0,0 +1,45 @@
+-- Existing progress, recents and favorites are given to a default user, created as an administrator
+-- when there isn't any user yet. Its password is set from PUSTAKA_ADMIN_PASSWORD on server start.
+INSERT INTO user (username, password_hash, is_admin)
+SELECT 'admin', '', 1 WHERE NOT EXISTS (SELECT 1 FROM user);
+
+CREATE TABLE publication_progress_backup (
+  user_id INTEGER NOT NULL,
+  publication_id INT NOT NULL,
+  progress FLOAT NOT NULL,
+  PRIMARY KEY (user_id, publication_id),
+  FOREIGN KEY(user_id) REFERENCES user(id),
+  FOREIGN KEY(publication_id) REFERENCES publication(id)
+);
+INSERT INTO publication_progress_backup
+SELECT (SELECT id FROM user ORDER BY is_admin DESC, id LIMIT 1), publication_id, progress
+FROM publication_progress;
+DROP TABLE publication_progress;
+ALTER TABLE publication_progress_backup RENAME TO publication_progress;
+
+CREATE TABLE recent_publication_backup (
+  user_id INTEGER NOT NULL,
+  publication_id INT NOT NULL,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (user_id, publication_id),
+  FOREIGN KEY(user_id) REFERENCES user(id),
+  FOREIGN KEY(publication_id) REFERENCES publication(id)
+);
+INSERT INTO recent_publication_backup
+SELECT (SELECT id FROM user ORDER BY is_admin DESC, id LIMIT 1), publication_id, timestamp
+FROM recent_publication;
+DROP TABLE recent_publication;
+ALTER TABLE recent_publication_backup RENAME TO recent_publication;
+
+CREATE TABLE favorite_category_backup (
+  user_id INTEGER NOT NULL,
+  category_id INT NOT NULL,
+  PRIMARY KEY (user_id, category_id),
+  FOREIGN KEY(user_id) REFERENCES user(id),
+  FOREIGN KEY(category_id) REFERENCES category(id)
+);
+INSERT INTO favorite_category_backup
+SELECT (SELECT id FROM user ORDER BY is_admin DESC, id LIMIT 1), category_id
+FROM favorite_category;
+DROP TABLE favorite_category;
+ALTER TABLE favorite_category_backup RENAME TO favorite_category;
@@ -0,0 +1,6 @@
+ALTER TABLE publication ADD COLUMN file_size BIGINT NULL;
+ALTER TABLE publication ADD COLUMN file_modified DATETIME NULL;
+ALTER TABLE publication ADD COLUMN file_hash VARCHAR NULL;
+ALTER TABLE publication ADD COLUMN missing BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE INDEX publication_file_size ON publication (file_size);
@@ -0,0 +1,11 @@
+CREATE TABLE setting_backup(
+  setting_id INT NOT NULL PRIMARY KEY,
+  publication_path VARCHAR NULL
+);
+INSERT INTO setting_backup (setting_id, publication_path)
+SELECT setting_id, (SELECT path FROM library ORDER BY id LIMIT 1) FROM setting;
+DROP TABLE setting;
+ALTER TABLE setting_backup RENAME TO setting;
+
+DROP INDEX library_path;
+DROP TABLE library;
@@ -0,0 +1,25 @@
+CREATE TABLE library(
+  id INTEGER NOT NULL PRIMARY KEY,
+  name VARCHAR NOT NULL,
+  path VARCHAR NOT NULL,
+  media_type_id INTEGER NOT NULL DEFAULT 1,
+  category_id INTEGER NULL,
+  FOREIGN KEY(media_type_id) REFERENCES media_type(id),
+  FOREIGN KEY(category_id) REFERENCES category(id)
+);
+
+CREATE UNIQUE INDEX library_path ON library(path);
+
+INSERT INTO library (name, path)
+SELECT 'Library', publication_path FROM setting
+WHERE publication_path IS NOT NULL AND publication_path != ''
+LIMIT 1;
+
+CREATE TABLE setting_backup(
+  setting_id INT NOT NULL PRIMARY KEY
+);
+INSERT INTO setting_backup SELECT setting_id FROM setting;
+DROP TABLE setting;
+ALTER TABLE setting_backup RENAME TO setting;
+
+INSERT OR IGNORE INTO setting (setting_id) VALUES (1);
@@ -0,0 +1,14 @@
+DELETE FROM category
+WHERE name = 'Uncategorized'
+  AND id NOT IN (SELECT category_id FROM publication_category)
+  AND id NOT IN (SELECT category_id FROM favorite_category)
+  AND id NOT IN (SELECT category_id FROM library WHERE category_id IS NOT NULL);
+
+CREATE TABLE setting_backup(
+  setting_id INT NOT NULL PRIMARY KEY
+);
+INSERT INTO setting_backup SELECT setting_id FROM setting;
+DROP TABLE setting;
+ALTER TABLE setting_backup RENAME TO setting;
+
+DROP TABLE category_rule;
@@ -0,0 +1,14 @@
+CREATE TABLE category_rule(
+  id INTEGER NOT NULL PRIMARY KEY,
+  pattern VARCHAR NOT NULL,
+  kind TEXT CHECK(kind IN ('glob', 'regex')) NOT NULL,
+  category_id INTEGER NOT NULL,
+  priority INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY(category_id) REFERENCES category(id)
+);
+
+ALTER TABLE setting ADD COLUMN min_category_score DOUBLE NOT NULL DEFAULT 0.5;
+
+INSERT INTO category (name)
+SELECT 'Uncategorized'
+WHERE NOT EXISTS (SELECT 1 FROM category WHERE name = 'Uncategorized');
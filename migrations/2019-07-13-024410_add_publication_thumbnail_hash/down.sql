@@ -0,0 +1,75 @@
+DROP TABLE ignored_file;
+
+DROP INDEX publication_file_size;
+
+CREATE TABLE publication_backup (
+  id INTEGER NOT NULL PRIMARY KEY,
+  isbn VARCHAR NOT NULL,
+  title VARCHAR NOT NULL,
+  media_type_id INTEGER NOT NULL,
+  media_format TEXT CHECK(media_format IN ('cbr', 'cbz', 'epub', 'pdf')) NOT NULL,
+  author_id INTEGER NOT NULL,
+  thumbnail VARCHAR NULL,
+  file VARCHAR NOT NULL,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
+  language VARCHAR NULL,
+  series VARCHAR NULL,
+  issue VARCHAR NULL,
+  file_size BIGINT NULL,
+  file_modified DATETIME NULL,
+  file_hash VARCHAR NULL,
+  missing BOOLEAN NOT NULL DEFAULT 0,
+  FOREIGN KEY(media_type_id) REFERENCES media_type(id),
+  FOREIGN KEY(author_id) REFERENCES author(id)
+);
+
+INSERT INTO publication_backup
+  SELECT id, isbn, title, media_type_id, media_format, author_id, thumbnail, file, timestamp,
+    language, series, issue, file_size, file_modified, file_hash, missing
+  FROM publication;
+
+-- Triggers on other tables refer to publication, which the rename would otherwise reject while
+-- the table is gone.
+PRAGMA legacy_alter_table = ON;
+DROP TABLE publication;
+ALTER TABLE publication_backup RENAME TO publication;
+PRAGMA legacy_alter_table = OFF;
+
+CREATE INDEX publication_file_size ON publication (file_size);
+
+-- Dropping the table took its triggers with it.
+CREATE TRIGGER publication_search_publication_insert AFTER INSERT ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.id;
+END;
+
+CREATE TRIGGER publication_search_publication_update AFTER UPDATE ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = NEW.id;
+  INSERT INTO publication_search (publication_id, title, author, tags, categories)
+    SELECT p.id, p.title, COALESCE(a.name, ''),
+      COALESCE((SELECT group_concat(t.name, ' ') FROM publication_tag pt
+        INNER JOIN tag t ON t.id = pt.tag_id WHERE pt.publication_id = p.id), ''),
+      COALESCE((SELECT group_concat(c.name, ' ') FROM publication_category pc
+        INNER JOIN category c ON c.id = pc.category_id WHERE pc.publication_id = p.id), '')
+    FROM publication p LEFT JOIN author a ON a.id = p.author_id
+    WHERE p.id = NEW.id;
+END;
+
+CREATE TRIGGER publication_search_publication_delete AFTER DELETE ON publication
+BEGIN
+  DELETE FROM publication_search WHERE publication_id = OLD.id;
+END;
+
+CREATE TRIGGER publication_content_search_publication_delete AFTER DELETE ON publication
+BEGIN
+  DELETE FROM publication_content_search WHERE publication_id = OLD.id;
+END;
@@ -0,0 +1,8 @@
+ALTER TABLE publication ADD COLUMN thumbnail_hash BIGINT NULL;
+
+-- Files that were merged into another publication, so that scans don't import them again.
+CREATE TABLE ignored_file (
+  file_hash VARCHAR NOT NULL PRIMARY KEY,
+  file VARCHAR NOT NULL,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP
+);
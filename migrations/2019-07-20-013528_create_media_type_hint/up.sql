@@ -0,0 +1,8 @@
+CREATE TABLE media_type_hint(
+  id INTEGER NOT NULL PRIMARY KEY,
+  folder VARCHAR NOT NULL COLLATE NOCASE,
+  media_type_id INTEGER NOT NULL,
+  FOREIGN KEY(media_type_id) REFERENCES media_type(id)
+);
+
+CREATE UNIQUE INDEX media_type_hint_folder ON media_type_hint(folder);
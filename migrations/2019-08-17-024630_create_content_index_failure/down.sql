@@ -0,0 +1,2 @@
+DROP TRIGGER content_index_failure_publication_delete;
+DROP TABLE content_index_failure;
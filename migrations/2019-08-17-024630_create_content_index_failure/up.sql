@@ -0,0 +1,14 @@
+-- EPUBs whose content couldn't be extracted, so that scans only try them again once the file
+-- changes.
+CREATE TABLE content_index_failure (
+  publication_id INTEGER NOT NULL PRIMARY KEY,
+  file_size BIGINT NULL,
+  file_modified DATETIME NULL,
+  timestamp DATETIME NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY(publication_id) REFERENCES publication(id)
+);
+
+CREATE TRIGGER content_index_failure_publication_delete AFTER DELETE ON publication
+BEGIN
+  DELETE FROM content_index_failure WHERE publication_id = OLD.id;
+END;
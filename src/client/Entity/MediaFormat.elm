@@ -8,7 +8,11 @@ type MediaFormat
     = NoMediaFormat
     | CBR
     | CBZ
+    | CB7
+    | CBT
+    | Folder
     | Epub
+    | PDF
 
 
 none : MediaFormat
@@ -28,9 +32,21 @@ decoder =
                     "cbz" ->
                         JD.succeed CBZ
 
+                    "cb7" ->
+                        JD.succeed CB7
+
+                    "cbt" ->
+                        JD.succeed CBT
+
+                    "folder" ->
+                        JD.succeed Folder
+
                     "epub" ->
                         JD.succeed Epub
 
+                    "pdf" ->
+                        JD.succeed PDF
+
                     "" ->
                         JD.succeed NoMediaFormat
 
@@ -48,9 +64,21 @@ encode mediaFormat =
         CBZ ->
             JE.string "cbz"
 
+        CB7 ->
+            JE.string "cb7"
+
+        CBT ->
+            JE.string "cbt"
+
+        Folder ->
+            JE.string "folder"
+
         Epub ->
             JE.string "epub"
 
+        PDF ->
+            JE.string "pdf"
+
         NoMediaFormat ->
             JE.string ""
 
@@ -64,8 +92,20 @@ toString mediaFormat =
         CBZ ->
             "cbz"
 
+        CB7 ->
+            "cb7"
+
+        CBT ->
+            "cbt"
+
+        Folder ->
+            "folder"
+
         Epub ->
             "epub"
 
+        PDF ->
+            "pdf"
+
         NoMediaFormat ->
             ""
@@ -3,6 +3,7 @@ module Entity.Publication exposing
     , Id
     , MetaData
     , Page
+    , Paginated
     , addThumbnail
     , deleteThumbnail
     , downloadCover
@@ -12,6 +13,7 @@ module Entity.Publication exposing
     , getProgress
     , getRecentlyAdded
     , getRecentlyRead
+    , hasMore
     , id
     , idToInt
     , idToString
@@ -32,7 +34,6 @@ import Json.Decode as JD
 import Json.Encode as JE
 import ReloadableData exposing (ReloadableWebData)
 import ReloadableData.Http
-import Task exposing (Task)
 
 
 type Id
@@ -105,6 +106,19 @@ type alias Page =
     }
 
 
+type alias Paginated a =
+    { items : List a
+    , total : Int
+    , page : Int
+    , perPage : Int
+    }
+
+
+hasMore : Paginated a -> Bool
+hasMore paginated =
+    paginated.page * paginated.perPage < paginated.total
+
+
 
 -- HTTP
 
@@ -157,13 +171,17 @@ updateProgress { publicationId, progress, msg } =
         }
 
 
-listByCategory : { categoryId : Int, msg : ReloadableWebData () (List MetaData) -> msg } -> Cmd msg
-listByCategory { categoryId, msg } =
+listByCategory : { categoryId : Int, page : Int, msg : ReloadableWebData Int (Paginated MetaData) -> msg } -> Cmd msg
+listByCategory { categoryId, page, msg } =
     ReloadableData.Http.get
-        { initial = ()
-        , url = "/api/publication/category/" ++ String.fromInt categoryId
+        { initial = page
+        , url =
+            "/api/publication/category/"
+                ++ String.fromInt categoryId
+                ++ "?page="
+                ++ String.fromInt page
         , msg = msg
-        , decoder = JD.list metaDataDecoder
+        , decoder = paginatedDecoder metaDataDecoder
         }
 
 
@@ -246,6 +264,15 @@ deleteThumbnail { publicationId, msg } =
 -- DECODER
 
 
+paginatedDecoder : JD.Decoder a -> JD.Decoder (Paginated a)
+paginatedDecoder itemDecoder =
+    JD.map4 Paginated
+        (JD.field "items" (JD.list itemDecoder))
+        (JD.field "total" JD.int)
+        (JD.field "page" JD.int)
+        (JD.field "per_page" JD.int)
+
+
 metaDataDecoder : JD.Decoder MetaData
 metaDataDecoder =
     JD.map6 MetaData
@@ -0,0 +1,25 @@
+module Entity.User exposing (login)
+
+import Json.Decode as JD
+import Json.Encode as JE
+import ReloadableData exposing (ReloadableWebData)
+import ReloadableData.Http
+
+
+
+-- HTTP
+
+
+login : { username : String, password : String, msg : ReloadableWebData () () -> msg } -> Cmd msg
+login { username, password, msg } =
+    ReloadableData.Http.post
+        { initial = ()
+        , url = "/api/auth/login"
+        , msg = msg
+        , decoder = JD.succeed ()
+        , json =
+            JE.object
+                [ ( "username", JE.string username )
+                , ( "password", JE.string password )
+                ]
+        }
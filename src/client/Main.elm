@@ -8,14 +8,17 @@ import Browser.Navigation as Nav
 import Entity.Category exposing (Category)
 import Html
 import Html.Styled as H exposing (..)
+import Http
 import Page.ByCategory as ByCategoryPage
 import Page.Home as HomePage
+import Page.Login as LoginPage
 import Page.Problem as ProblemPage
 import Page.Publication as PublicationPage
 import Page.Publication.Edit as PublicationEditPage
 import Page.Read as ReadPage
 import ReloadableData exposing (ReloadableWebData)
 import Return
+import Route
 import Task
 import UI.Layout
 import Url
@@ -55,6 +58,7 @@ type Page
     | Publication PublicationPage.Model
     | PublicationEdit PublicationEditPage.Model
     | Read ReadPage.Model
+    | Login LoginPage.Model
     | Problem String
 
 
@@ -103,6 +107,7 @@ type PageMsg
     | PublicationMsg PublicationPage.Msg
     | ReadMsg ReadPage.Msg
     | PublicationEditMsg PublicationEditPage.Msg
+    | LoginMsg LoginPage.Msg
 
 
 
@@ -142,6 +147,10 @@ view model =
             ReadPage.view model.viewport readModel
                 |> mapPage (PageMsg << ReadMsg)
 
+        Login loginModel ->
+            LoginPage.view model loginModel
+                |> mapPage (PageMsg << LoginMsg)
+
         Problem text ->
             ProblemPage.view text
 
@@ -184,7 +193,15 @@ update msg model =
             ( { model | viewport = viewport }, Cmd.none )
 
         LoadFavoriteCompleted data ->
-            ( { model | favoriteCategories = data }, Cmd.none )
+            case ( ReloadableData.toError data, model.page ) of
+                ( Just (Http.BadStatus 401), Login _ ) ->
+                    ( model, Cmd.none )
+
+                ( Just (Http.BadStatus 401), _ ) ->
+                    ( model, Nav.replaceUrl model.key Route.loginUrl )
+
+                _ ->
+                    ( { model | favoriteCategories = data }, Cmd.none )
 
         UrlChanged url ->
             stepUrl url model
@@ -219,6 +236,10 @@ pageUpdate msg model =
             PublicationEditPage.update model.key pageMsg pageModel
                 |> Return.mapBoth (PageMsg << PublicationEditMsg) (updatePage model PublicationEdit)
 
+        ( LoginMsg pageMsg, Login pageModel ) ->
+            LoginPage.update model.key pageMsg pageModel
+                |> Return.mapBoth (PageMsg << LoginMsg) (updatePage model Login)
+
         _ ->
             ( model, Cmd.none )
 
@@ -276,6 +297,8 @@ stepUrl url model =
                     (\pubId -> stepPublicationEdit model (PublicationEditPage.init pubId))
                 , route (top </> s "read" </> int)
                     (\pubId -> stepRead model (ReadPage.init pubId Nothing))
+                , route (top </> s "login")
+                    (stepLogin model LoginPage.init)
                 ]
     in
     case Parser.parse parser url of
@@ -329,3 +352,10 @@ stepRead model ( readModel, cmds ) =
     ( { model | page = Read readModel }
     , Cmd.map (PageMsg << ReadMsg) cmds
     )
+
+
+stepLogin : Model -> ( LoginPage.Model, Cmd LoginPage.Msg ) -> ( Model, Cmd Msg )
+stepLogin model ( loginModel, cmds ) =
+    ( { model | page = Login loginModel }
+    , Cmd.map (PageMsg << LoginMsg) cmds
+    )
@@ -42,6 +42,7 @@ import UI.Spacing as UI
 type alias Model =
     { selectedCategoryId : Maybe (ReloadableWebData Int Category)
     , publications : ReloadableWebData () (List Publication.MetaData)
+    , nextPage : Maybe Int
     , categories : ReloadableWebData () (List Category)
     , recentlyAddedPublications : Dict Int (ReloadableWebData () (List Publication.MetaData))
     , recentlyReadPublications : ReloadableWebData () (List Publication.MetaData)
@@ -61,6 +62,7 @@ initialModel : Model
 initialModel =
     { selectedCategoryId = Nothing
     , publications = ReloadableData.NotAsked ()
+    , nextPage = Nothing
     , categories = ReloadableData.NotAsked ()
     , recentlyAddedPublications = Dict.empty
     , recentlyReadPublications = ReloadableData.NotAsked ()
@@ -77,7 +79,8 @@ type Msg
     = NoOp
     | LinkClicked String
     | CategorySelected (Maybe Int)
-    | GetPublicationCompleted (ReloadableWebData () (List Publication.MetaData))
+    | GetPublicationCompleted Int (ReloadableWebData Int (Publication.Paginated Publication.MetaData))
+    | LoadMoreClicked
     | CoverDownloaded Int (ReloadableWebData () Image)
     | GetRecentlyReadPublicationCompleted (ReloadableWebData () (List Publication.MetaData))
     | GetCategoriesCompleted (ReloadableWebData () (List Category))
@@ -218,9 +221,25 @@ viewPublications model publications =
                     ]
                 ]
                 (publications |> List.map (publicationView model))
+            , viewLoadMore model
             ]
 
 
+viewLoadMore : Model -> Html Msg
+viewLoadMore model =
+    case ( model.nextPage, model.publications ) of
+        ( Nothing, _ ) ->
+            text ""
+
+        ( Just _, ReloadableData.Reloading _ _ ) ->
+            div [ css [ displayFlex, justifyContent center, UI.padding UI.Medium ] ]
+                [ Action.toHtml <| Action.large <| Action.disable { text = "Loading", icon = Icon.spinner Icon.small } ]
+
+        ( Just _, _ ) ->
+            div [ css [ displayFlex, justifyContent center, UI.padding UI.Medium ] ]
+                [ Action.toHtml <| Action.large <| Action.clickable { text = "Load more", icon = Icon.expandMore Icon.small, onClick = LoadMoreClicked } ]
+
+
 publicationView : Model -> Publication.MetaData -> Html Msg
 publicationView model publication =
     let
@@ -292,25 +311,72 @@ update key msg model =
         CategorySelected selectedCategoryId ->
             selectCategory selectedCategoryId model
 
-        GetPublicationCompleted publications ->
-            ( { model
-                | publications = publications
-                , covers =
-                    publications
-                        |> ReloadableData.toMaybe
-                        |> Maybe.withDefault []
-                        |> List.filterMap
-                            (\pub ->
-                                if Thumbnail.hasThumbnail pub.thumbnail then
-                                    Just ( pub.id, ReloadableData.Loading () )
+        GetPublicationCompleted categoryId data ->
+            if Just categoryId /= selectedId model then
+                ( model, Cmd.none )
+
+            else
+                case data of
+                    ReloadableData.Success page paginated ->
+                        let
+                            loaded =
+                                if page == 1 then
+                                    []
+
+                                else
+                                    ReloadableData.withDefault [] model.publications
+
+                            covers =
+                                paginated.items
+                                    |> List.filterMap
+                                        (\pub ->
+                                            if Thumbnail.hasThumbnail pub.thumbnail then
+                                                Just ( pub.id, ReloadableData.Loading () )
+
+                                            else
+                                                Nothing
+                                        )
+                                    |> Dict.fromList
+                        in
+                        ( { model
+                            | publications = ReloadableData.Success () (loaded ++ paginated.items)
+                            , nextPage =
+                                if Publication.hasMore paginated then
+                                    Just (page + 1)
 
                                 else
                                     Nothing
-                            )
-                        |> Dict.fromList
-              }
-            , downloadCovers publications
-            )
+                            , covers =
+                                if page == 1 then
+                                    covers
+
+                                else
+                                    Dict.union covers model.covers
+                          }
+                        , downloadCovers (ReloadableData.Success () paginated.items)
+                        )
+
+                    ReloadableData.Failure error _ ->
+                        ( { model | publications = ReloadableData.setError error model.publications }
+                        , Cmd.none
+                        )
+
+                    _ ->
+                        ( model, Cmd.none )
+
+        LoadMoreClicked ->
+            case ( selectedId model, model.nextPage ) of
+                ( Just categoryId, Just page ) ->
+                    ( { model | publications = ReloadableData.loading model.publications }
+                    , Publication.listByCategory
+                        { categoryId = categoryId
+                        , page = page
+                        , msg = GetPublicationCompleted categoryId
+                        }
+                    )
+
+                _ ->
+                    ( model, Cmd.none )
 
         CoverDownloaded publicationId data ->
             ( { model
@@ -400,6 +466,7 @@ selectCategory selectedCategoryId model =
             selectedCategoryId
                 |> Maybe.map (\_ -> ReloadableData.loading model.publications)
                 |> Maybe.withDefault (ReloadableData.NotAsked ())
+        , nextPage = Nothing
       }
     , selectedCategoryId
         |> Maybe.map
@@ -407,7 +474,8 @@ selectCategory selectedCategoryId model =
                 Cmd.batch
                     [ Publication.listByCategory
                         { categoryId = id
-                        , msg = GetPublicationCompleted
+                        , page = 1
+                        , msg = GetPublicationCompleted id
                         }
                     , Category.get { categoryId = id, msg = GetCategoryCompleted }
                     ]
@@ -416,6 +484,11 @@ selectCategory selectedCategoryId model =
     )
 
 
+selectedId : Model -> Maybe Int
+selectedId model =
+    model.selectedCategoryId |> Maybe.map ReloadableData.toInitial
+
+
 selectedItem : Maybe (ReloadableWebData Int Category) -> UI.Nav.SelectedItem
 selectedItem selectedCategoryId =
     case selectedCategoryId |> Maybe.map ReloadableData.toInitial of
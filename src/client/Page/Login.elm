@@ -0,0 +1,172 @@
+module Page.Login exposing
+    ( Model
+    , Msg
+    , init
+    , update
+    , view
+    )
+
+import Assets exposing (Assets)
+import Browser
+import Browser.Navigation as Nav
+import Css exposing (..)
+import Entity.User as User
+import Html.Styled as H exposing (..)
+import Html.Styled.Attributes as HA exposing (css)
+import Html.Styled.Events as HE
+import Http
+import ReloadableData exposing (ReloadableWebData)
+import Route
+import UI.Action as Action
+import UI.Css.Grid as Grid
+import UI.Error
+import UI.Heading as UI exposing (Level(..))
+import UI.Icon as Icon
+import UI.Reset exposing (reset)
+import UI.Spacing as UI
+
+
+
+-- MODEL
+
+
+type alias Model =
+    { username : String
+    , password : String
+    , submission : ReloadableWebData () ()
+    }
+
+
+init : ( Model, Cmd Msg )
+init =
+    ( { username = ""
+      , password = ""
+      , submission = ReloadableData.NotAsked ()
+      }
+    , Cmd.none
+    )
+
+
+
+-- MESSAGE
+
+
+type Msg
+    = UsernameChanged String
+    | PasswordChanged String
+    | FormSubmitted
+    | LoginCompleted (ReloadableWebData () ())
+
+
+
+-- VIEW
+
+
+view : { a | assets : Assets } -> Model -> Browser.Document Msg
+view { assets } model =
+    { title = "Pustaka - Login"
+    , body =
+        [ H.toUnstyled <| reset
+        , H.toUnstyled <|
+            div
+                [ css
+                    [ displayFlex
+                    , justifyContent center
+                    , alignItems center
+                    , height (vh 100)
+                    ]
+                ]
+                [ H.form
+                    [ HE.onSubmit FormSubmitted
+                    , css
+                        [ Grid.display
+                        , Grid.rowGap 20
+                        , width (px 320)
+                        , UI.padding UI.Large
+                        ]
+                    ]
+                    [ img [ css [ height (px 30) ], HA.src assets.logoText, HA.alt "Pustaka" ] []
+                    , UI.heading One "Login"
+                    , viewError model.submission
+                    , viewField
+                        { label = "Username"
+                        , type_ = "text"
+                        , value = model.username
+                        , onChange = UsernameChanged
+                        }
+                    , viewField
+                        { label = "Password"
+                        , type_ = "password"
+                        , value = model.password
+                        , onChange = PasswordChanged
+                        }
+                    , Action.toHtml <|
+                        Action.large <|
+                            Action.clickable
+                                { text = "Login"
+                                , icon = Icon.next Icon.small
+                                , onClick = FormSubmitted
+                                }
+                    , input [ HA.type_ "submit", HA.hidden True ] []
+                    ]
+                ]
+        ]
+    }
+
+
+viewField : { label : String, type_ : String, value : String, onChange : String -> Msg } -> Html Msg
+viewField { label, type_, value, onChange } =
+    H.label
+        [ css
+            [ width (pct 100)
+            , displayFlex
+            , flexDirection column
+            ]
+        ]
+        [ text label
+        , input [ HA.type_ type_, HE.onInput onChange, HA.value value ] []
+        ]
+
+
+viewError : ReloadableWebData () () -> Html Msg
+viewError submission =
+    case ReloadableData.toError submission of
+        Just (Http.BadStatus 401) ->
+            UI.Error.string "Wrong username or password"
+
+        Just error ->
+            UI.Error.http error
+
+        Nothing ->
+            text ""
+
+
+
+-- UPDATE
+
+
+update : Nav.Key -> Msg -> Model -> ( Model, Cmd Msg )
+update key msg model =
+    case msg of
+        UsernameChanged username ->
+            ( { model | username = username }, Cmd.none )
+
+        PasswordChanged password ->
+            ( { model | password = password }, Cmd.none )
+
+        FormSubmitted ->
+            ( { model | submission = ReloadableData.Loading () }
+            , User.login
+                { username = model.username
+                , password = model.password
+                , msg = LoginCompleted
+                }
+            )
+
+        LoginCompleted data ->
+            case data of
+                ReloadableData.Success _ _ ->
+                    ( { model | submission = data }, Nav.load Route.homeUrl )
+
+                _ ->
+                    ( { model | submission = data, password = "" }, Cmd.none )
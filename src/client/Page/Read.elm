@@ -332,6 +332,27 @@ updateCompletedData viewport data model =
                         |> extract
                         |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map ComicMsg)
 
+                Just MediaFormat.CB7 ->
+                    data
+                        |> ReloadableData.mapErr HttpError
+                        |> ReloadableData.map (\publication -> Comic.init viewport publication |> Tuple.mapFirst (Comic publication))
+                        |> extract
+                        |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map ComicMsg)
+
+                Just MediaFormat.CBT ->
+                    data
+                        |> ReloadableData.mapErr HttpError
+                        |> ReloadableData.map (\publication -> Comic.init viewport publication |> Tuple.mapFirst (Comic publication))
+                        |> extract
+                        |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map ComicMsg)
+
+                Just MediaFormat.Folder ->
+                    data
+                        |> ReloadableData.mapErr HttpError
+                        |> ReloadableData.map (\publication -> Comic.init viewport publication |> Tuple.mapFirst (Comic publication))
+                        |> extract
+                        |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map ComicMsg)
+
                 Just MediaFormat.Epub ->
                     data
                         |> ReloadableData.mapErr HttpError
@@ -339,6 +360,13 @@ updateCompletedData viewport data model =
                         |> extract
                         |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map EpubMsg)
 
+                Just MediaFormat.PDF ->
+                    data
+                        |> ReloadableData.mapErr HttpError
+                        |> ReloadableData.map (\publication -> Comic.init viewport publication |> Tuple.mapFirst (Comic publication))
+                        |> extract
+                        |> Tuple.mapSecond (Maybe.withDefault Cmd.none >> Cmd.map ComicMsg)
+
                 Just MediaFormat.NoMediaFormat ->
                     data
                         |> ReloadableData.mapErr HttpError
@@ -19,6 +19,7 @@ import Browser.Navigation as Nav
 import Cmd
 import Css exposing (..)
 import Entity.Image as Image exposing (Image, ReloadableImage)
+import Entity.MediaFormat as MediaFormat
 import Entity.Progress as Progress exposing (Progress)
 import Entity.Publication as Publication
 import Html.Styled as H exposing (..)
@@ -27,6 +28,7 @@ import Html.Styled.Events as HE exposing (onClick)
 import Keyboard
 import Reader.ComicPage as ComicPage exposing (ComicPage)
 import ReloadableData exposing (ReloadableWebData)
+import Task
 import UI.Css.Grid as Grid
 import UI.Error
 import UI.Events
@@ -160,17 +162,17 @@ setSinglePage data pageLayout =
 
 
 reader : { viewport : Viewport, publication : Publication.Data, model : Model } -> Html Msg
-reader ({ model } as args) =
+reader ({ model, publication } as args) =
     case model.pageLayout of
         SinglePageLayout page ->
-            singlePageReader model.pageLayout page
+            singlePageReader publication model.pageLayout page
 
         DoublePagesLayout pages ->
-            dualPagesReader model.pageLayout pages
+            dualPagesReader publication model.pageLayout pages
 
 
-singlePageReader : PageLayout -> ComicPage -> Html Msg
-singlePageReader pageLayout page =
+singlePageReader : Publication.Data -> PageLayout -> ComicPage -> Html Msg
+singlePageReader publication pageLayout page =
     div
         [ css
             [ width (pct 100)
@@ -191,13 +193,14 @@ singlePageReader pageLayout page =
                 , overflowY auto
                 ]
             )
+            publication
             pageLayout
             page
         ]
 
 
-dualPagesReader : PageLayout -> { left : ComicPage, right : ComicPage } -> Html Msg
-dualPagesReader pageLayout { left, right } =
+dualPagesReader : Publication.Data -> PageLayout -> { left : ComicPage, right : ComicPage } -> Html Msg
+dualPagesReader publication pageLayout { left, right } =
     div
         [ css
             [ width (pct 100)
@@ -221,6 +224,7 @@ dualPagesReader pageLayout { left, right } =
             ]
             [ viewPage
                 (css [ maxWidth (pct 100) ])
+                publication
                 pageLayout
                 left
             ]
@@ -236,13 +240,14 @@ dualPagesReader pageLayout { left, right } =
                 , overflowY auto
                 ]
             )
+            publication
             pageLayout
             right
         ]
 
 
-viewPage : Attribute Msg -> PageLayout -> ComicPage -> Html Msg
-viewPage alignment pageLayout page =
+viewPage : Attribute Msg -> Publication.Data -> PageLayout -> ComicPage -> Html Msg
+viewPage alignment publication pageLayout page =
     case page of
         ComicPage.Empty ->
             text ""
@@ -251,7 +256,12 @@ viewPage alignment pageLayout page =
             UI.ReloadableData.view
                 (\pageImage ->
                     div [ alignment ]
-                        [ Image.fullHeight pageImage
+                        [ case publication.mediaFormat of
+                            MediaFormat.PDF ->
+                                pdfPage publication.id number
+
+                            _ ->
+                                Image.fullHeight pageImage
                         , viewPageNumber pageLayout number
                         ]
                 )
@@ -346,6 +356,19 @@ next =
         [ Icon.next Icon.large ]
 
 
+pdfPage : Int -> Int -> Html msg
+pdfPage pubId pageNum =
+    H.iframe
+        [ HA.src <| imageUrl pubId pageNum
+        , css
+            [ height (vh 100)
+            , width (pct 100)
+            , borderWidth zero
+            ]
+        ]
+        []
+
+
 image : Int -> Int -> Html msg
 image pubId pageNum =
     H.img
@@ -471,11 +494,7 @@ updateSinglePageProgress publication unclampedPercentage page model =
                     |> ComicPage.toPageNumber
                     |> Maybe.map
                         (\pageNumber ->
-                            Publication.downloadPage
-                                { publicationId = publication.id
-                                , page = pageNumber
-                                , msg = SingleImageLoaded
-                                }
+                            loadPage publication pageNumber SingleImageLoaded
                         )
                     |> Maybe.withDefault Cmd.none
                 ]
@@ -516,22 +535,14 @@ updateDoublePagesProgress publication unclampedPercentage { left, right } model
                     |> ComicPage.toPageNumber
                     |> Maybe.map
                         (\pageNumber ->
-                            Publication.downloadPage
-                                { publicationId = publication.id
-                                , page = pageNumber
-                                , msg = LeftImageLoaded
-                                }
+                            loadPage publication pageNumber LeftImageLoaded
                         )
                     |> Maybe.withDefault Cmd.none
                 , rightPage
                     |> ComicPage.toPageNumber
                     |> Maybe.map
                         (\pageNumber ->
-                            Publication.downloadPage
-                                { publicationId = publication.id
-                                , page = pageNumber
-                                , msg = RightImageLoaded
-                                }
+                            loadPage publication pageNumber RightImageLoaded
                         )
                     |> Maybe.withDefault Cmd.none
                 ]
@@ -563,6 +574,21 @@ calculateNextPercentage operator pageLayout publication progress =
         |> ReloadableData.map (\float -> operator float (delta * multiplier))
 
 
+loadPage : Publication.Data -> Int -> (ReloadableImage -> Msg) -> Cmd Msg
+loadPage publication pageNumber msg =
+    case publication.mediaFormat of
+        MediaFormat.PDF ->
+            Task.succeed (ReloadableData.Success () Image.none)
+                |> Task.perform msg
+
+        _ ->
+            Publication.downloadPage
+                { publicationId = publication.id
+                , page = pageNumber
+                , msg = msg
+                }
+
+
 submitProgress : Publication.Data -> Model -> Cmd Msg
 submitProgress publication model =
     model.progress
@@ -4,6 +4,7 @@ module Route exposing
     , browseByMediaTypeUrl
     , categoryUrl
     , homeUrl
+    , loginUrl
     , publicationEditUrl
     , publicationUrl
     , readUrl
@@ -25,6 +26,11 @@ homeUrl =
     baseUrl ++ "/"
 
 
+loginUrl : String
+loginUrl =
+    baseUrl ++ "/login"
+
+
 categoryUrl : Int -> String
 categoryUrl id =
     baseUrl ++ "/category/" ++ String.fromInt id
@@ -4,6 +4,7 @@ import Css exposing (..)
 import Html.Styled as H exposing (..)
 import Html.Styled.Attributes as HA exposing (css)
 import Http
+import Route
 import UI.Background
 import UI.Font
 import UI.Spacing
@@ -26,4 +27,12 @@ string error =
 
 http : Http.Error -> Html msg
 http error =
-    string <| "Error"
+    case error of
+        Http.BadStatus 401 ->
+            div []
+                [ string "Your session has expired"
+                , a [ HA.href Route.loginUrl ] [ text "Login again" ]
+                ]
+
+        _ ->
+            string <| "Error"
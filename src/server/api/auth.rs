@@ -0,0 +1,187 @@
+use actix_web::http::{header, Cookie, Method};
+use actix_web::middleware::{Middleware, Started};
+use actix_web::{
+    error, middleware, App, AsyncResponder, FromRequest, FutureResponse, HttpRequest, HttpResponse,
+    Json, Result,
+};
+use cookie::SameSite;
+use db::user::{Authenticate as AuthenticateUser, GetByUsername, Login, Logout};
+use futures::{future, Future};
+use models::User;
+use password::VerifyPassword;
+use state::AppState;
+
+pub const SESSION_COOKIE: &str = "pustaka_session";
+const BEARER: &str = "Bearer ";
+
+/// Middleware rejecting requests that don't carry a valid session, either as the session cookie
+/// or as an `Authorization: Bearer` token. The authenticated user is stored in the request
+/// extensions for the `CurrentUser` and `Admin` extractors.
+pub struct Authenticate;
+
+impl Middleware<AppState> for Authenticate {
+    fn start(&self, req: &HttpRequest<AppState>) -> Result<Started> {
+        let token = match session_token(req) {
+            Some(token) => token,
+            None => return Ok(Started::Response(HttpResponse::Unauthorized().finish())),
+        };
+
+        let req = req.clone();
+        let task = req.state().db.send(AuthenticateUser { token });
+        Ok(Started::Future(Box::new(task.from_err().and_then(
+            move |res| match res? {
+                Some(user) => {
+                    req.extensions_mut().insert(user);
+                    Ok(None)
+                }
+                None => Ok(Some(HttpResponse::Unauthorized().finish())),
+            },
+        ))))
+    }
+}
+
+fn session_token<S>(req: &HttpRequest<S>) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| value.starts_with(BEARER))
+        .map(|value| value[BEARER.len()..].trim().to_string());
+
+    bearer.or_else(|| {
+        req.cookie(SESSION_COOKIE)
+            .map(|cookie| cookie.value().to_string())
+    })
+}
+
+/// The user making the request. Only available behind the `Authenticate` middleware.
+pub struct CurrentUser(pub User);
+
+impl<S> FromRequest<S> for CurrentUser {
+    type Config = ();
+    type Result = Result<Self>;
+
+    fn from_request(req: &HttpRequest<S>, _: &Self::Config) -> Self::Result {
+        req.extensions()
+            .get::<User>()
+            .cloned()
+            .map(CurrentUser)
+            .ok_or(error::ErrorUnauthorized("Not logged in"))
+    }
+}
+
+/// Like `CurrentUser`, but rejects the request unless the user is an administrator.
+pub struct Admin(pub User);
+
+impl<S> FromRequest<S> for Admin {
+    type Config = ();
+    type Result = Result<Self>;
+
+    fn from_request(req: &HttpRequest<S>, config: &Self::Config) -> Self::Result {
+        let CurrentUser(user) = CurrentUser::from_request(req, config)?;
+        match user.is_admin {
+            true => Ok(Admin(user)),
+            false => Err(error::ErrorForbidden("Administrator only")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LoginResponse {
+    token: String,
+    user: User,
+}
+
+fn login(req: HttpRequest<AppState>, json: Json<Credentials>) -> FutureResponse<HttpResponse> {
+    let Credentials { username, password } = json.into_inner();
+    let secure = is_https(&req);
+    let db = req.state().db.clone();
+    let hasher = req.state().password.clone();
+    req.state()
+        .db
+        .send(GetByUsername { username })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(move |user| {
+            // an unknown user is checked against a dummy hash, so the time it takes doesn't
+            // tell whether the username exists
+            let hash = user.as_ref().map(|user| user.password_hash.clone());
+            hasher
+                .send(VerifyPassword { password, hash })
+                .from_err()
+                .map(move |verified| if verified { user } else { None })
+        })
+        .and_then(move |user| match user {
+            Some(user) => future::Either::A(
+                db.send(Login { user_id: user.id })
+                    .from_err()
+                    .and_then(|res| res)
+                    .map(move |session| Some((user, session))),
+            ),
+            None => future::Either::B(future::ok(None)),
+        })
+        .and_then(move |res| match res {
+            Some((user, session)) => Ok(HttpResponse::Ok()
+                .cookie(
+                    Cookie::build(SESSION_COOKIE, session.token.clone())
+                        .path("/")
+                        .http_only(true)
+                        .same_site(SameSite::Strict)
+                        .secure(secure)
+                        .finish(),
+                )
+                .json(LoginResponse {
+                    token: session.token,
+                    user,
+                })),
+            None => Ok(HttpResponse::Unauthorized().finish()),
+        })
+        .responder()
+}
+
+fn logout(req: HttpRequest<AppState>) -> FutureResponse<HttpResponse> {
+    let token = match session_token(&req) {
+        Some(token) => token,
+        None => return Box::new(future::ok(HttpResponse::Ok().json(()))),
+    };
+    let secure = is_https(&req);
+
+    req.state()
+        .db
+        .send(Logout { token })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(move |_| {
+            Ok(HttpResponse::Ok()
+                .del_cookie(
+                    &Cookie::build(SESSION_COOKIE, "")
+                        .path("/")
+                        .http_only(true)
+                        .same_site(SameSite::Strict)
+                        .secure(secure)
+                        .finish(),
+                )
+                .json(()))
+        })
+        .responder()
+}
+
+/// Whether the client reached us over HTTPS, also behind a proxy setting `X-Forwarded-Proto`, in
+/// which case the session cookie is only sent back over HTTPS.
+fn is_https(req: &HttpRequest<AppState>) -> bool {
+    req.connection_info().scheme() == "https"
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .prefix(prefix)
+        .route("/login", Method::POST, login)
+        .route("/logout", Method::POST, logout)
+}
@@ -1,5 +1,6 @@
 use actix_web::http::Method;
 use actix_web::{middleware, App, AsyncResponder, FutureResponse, HttpResponse, Json, Path, State};
+use api::auth::{Admin, Authenticate};
 use db::author::{Create, Delete, Get, List, Update};
 use futures::Future;
 use models::{Author, NewAuthor};
@@ -45,7 +46,11 @@ fn update(state: State<AppState>, json: Json<Author>) -> FutureResponse<HttpResp
         .responder()
 }
 
-fn delete(state: State<AppState>, author_id: Path<i32>) -> FutureResponse<HttpResponse> {
+fn delete(
+    state: State<AppState>,
+    author_id: Path<i32>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
     state
         .db
         .send(Delete {
@@ -76,6 +81,7 @@ fn get(state: State<AppState>, author_id: Path<i32>) -> FutureResponse<HttpRespo
 pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
     App::with_state(state)
         .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
         .prefix(prefix)
         .route("/", Method::GET, list)
         .route("/", Method::POST, create)
@@ -0,0 +1,38 @@
+use actix_web::http::Method;
+use actix_web::{error, middleware, App, HttpResponse, Path, Result, State};
+use api::auth::{Admin, Authenticate};
+use state::AppState;
+
+fn status(state: State<AppState>, _admin: Admin) -> HttpResponse {
+    HttpResponse::Ok().json(state.cache.status())
+}
+
+fn purge(state: State<AppState>, _admin: Admin) -> Result<HttpResponse> {
+    state
+        .cache
+        .purge()
+        .map_err(error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(state.cache.status()))
+}
+
+fn purge_publication(
+    state: State<AppState>,
+    publication_id: Path<i32>,
+    _admin: Admin,
+) -> Result<HttpResponse> {
+    state
+        .cache
+        .remove(publication_id.into_inner())
+        .map_err(error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(state.cache.status()))
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
+        .prefix(prefix)
+        .route("/", Method::GET, status)
+        .route("/", Method::DELETE, purge)
+        .route("/{publication_id}", Method::DELETE, purge_publication)
+}
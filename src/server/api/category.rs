@@ -3,15 +3,20 @@ extern crate diesel;
 use actix_web::http::Method;
 use actix_web::Json;
 use actix_web::{middleware, App, AsyncResponder, FutureResponse, HttpResponse, Path, State};
-use db::category::{Create, Delete, Favorite, Get, List, Update};
+use api::auth::{Admin, Authenticate, CurrentUser};
+use db::category::{
+    AddFavorite, Create, Delete, Favorite, Get, List, RemoveFavorite, Update,
+};
 use futures::Future;
-use models::{Category, NewCategory};
+use models::{Category, FavoriteCategory, NewCategory};
 use state::AppState;
 
-fn favorite(state: State<AppState>) -> FutureResponse<HttpResponse> {
+fn favorite(state: State<AppState>, current_user: CurrentUser) -> FutureResponse<HttpResponse> {
     state
         .db
-        .send(Favorite {})
+        .send(Favorite {
+            user_id: current_user.0.id,
+        })
         .from_err()
         .and_then(|res| match res {
             Ok(categories) => Ok(HttpResponse::Ok().json(categories)),
@@ -19,6 +24,40 @@ fn favorite(state: State<AppState>) -> FutureResponse<HttpResponse> {
         }).responder()
 }
 
+fn add_favorite(
+    state: State<AppState>,
+    category_id: Path<i32>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(AddFavorite(FavoriteCategory {
+            user_id: current_user.0.id,
+            category_id: category_id.into_inner(),
+        }))
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn remove_favorite(
+    state: State<AppState>,
+    category_id: Path<i32>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(RemoveFavorite(FavoriteCategory {
+            user_id: current_user.0.id,
+            category_id: category_id.into_inner(),
+        }))
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
 fn list(state: State<AppState>) -> FutureResponse<HttpResponse> {
     state
         .db
@@ -54,7 +93,11 @@ fn update(state: State<AppState>, json: Json<Category>) -> FutureResponse<HttpRe
         }).responder()
 }
 
-fn delete(state: State<AppState>, category_id: Path<i32>) -> FutureResponse<HttpResponse> {
+fn delete(
+    state: State<AppState>,
+    category_id: Path<i32>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
     state
         .db
         .send(Delete {
@@ -81,6 +124,7 @@ fn get(state: State<AppState>, category_id: Path<i32>) -> FutureResponse<HttpRes
 pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
     App::with_state(state)
         .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
         .prefix(prefix)
         .route("/", Method::GET, list)
         .route("/", Method::POST, create)
@@ -88,4 +132,6 @@ pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
         .route("/{category_id}", Method::DELETE, delete)
         .route("/{category_id}", Method::GET, get)
         .route("/favorite/", Method::GET, favorite)
+        .route("/favorite/{category_id}", Method::PUT, add_favorite)
+        .route("/favorite/{category_id}", Method::DELETE, remove_favorite)
 }
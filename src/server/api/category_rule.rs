@@ -0,0 +1,95 @@
+use actix_web::http::Method;
+use actix_web::{
+    error, middleware, App, AsyncResponder, FutureResponse, HttpResponse, Json, Path, State,
+};
+use api::auth::{Admin, Authenticate};
+use db::category_rule::{Create, Delete, List, Update};
+use futures::{future, Future};
+use models::{CategoryRule, NewCategoryRule};
+use scan::rule::Rule;
+use state::AppState;
+
+fn list(state: State<AppState>) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(List {})
+        .from_err()
+        .and_then(|res| match res {
+            Ok(rules) => Ok(HttpResponse::Ok().json(rules)),
+            Err(_) => Ok(HttpResponse::InternalServerError().into()),
+        })
+        .responder()
+}
+
+fn create(
+    state: State<AppState>,
+    json: Json<NewCategoryRule>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    let new_rule = json.into_inner();
+    if let Err(err) = Rule::compile(&CategoryRule {
+        id: 0,
+        pattern: new_rule.pattern.clone(),
+        kind: new_rule.kind.clone(),
+        category_id: new_rule.category_id,
+        priority: new_rule.priority,
+    }) {
+        return Box::new(future::err(error::ErrorBadRequest(err.to_string())));
+    }
+
+    state
+        .db
+        .send(Create { new_rule })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn update(
+    state: State<AppState>,
+    json: Json<CategoryRule>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    let rule = json.into_inner();
+    if let Err(err) = Rule::compile(&rule) {
+        return Box::new(future::err(error::ErrorBadRequest(err.to_string())));
+    }
+
+    state
+        .db
+        .send(Update { rule })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn delete(
+    state: State<AppState>,
+    rule_id: Path<i32>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(Delete {
+            rule_id: rule_id.into_inner(),
+        })
+        .from_err()
+        .and_then(|res| match res {
+            Ok(_) => Ok(HttpResponse::Ok().json(())),
+            Err(_) => Ok(HttpResponse::InternalServerError().into()),
+        })
+        .responder()
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
+        .prefix(prefix)
+        .route("/", Method::GET, list)
+        .route("/", Method::POST, create)
+        .route("/", Method::PUT, update)
+        .route("/{rule_id}", Method::DELETE, delete)
+}
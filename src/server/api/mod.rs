@@ -1,5 +1,11 @@
+pub mod auth;
 pub mod author;
+pub mod cache;
 pub mod category;
+pub mod category_rule;
 pub mod media_type;
 pub mod publication;
+pub mod scan;
+pub mod setting;
 pub mod tag;
+pub mod user;
@@ -1,21 +1,31 @@
 use actix::prelude::Addr;
-use actix_web::http::Method;
+use actix_web::fs::{file_extension_to_mime, NamedFile};
+use actix_web::http::{header, Method};
 use actix_web::{
-    dev, error, error::ErrorBadRequest, fs::NamedFile, middleware, multipart, App, AsyncResponder,
-    FutureResponse, HttpMessage, HttpRequest, HttpResponse, Json, Path, Result, State,
+    dev, error, error::ErrorBadRequest, middleware, multipart, App, AsyncResponder, FutureResponse,
+    HttpMessage, HttpRequest, HttpResponse, Json, Path, Query, Result, State,
 };
-use config::Config;
+use api::auth::{Admin, Authenticate, CurrentUser};
+use db::duplicate;
 use db::executor::DbExecutor;
 use db::publication::{
-    self, AddRecent, Delete, DeleteThumbnail, Get, GetProgress, List, ListByCategory,
-    ListRecentlyAdded, ListRecentlyRead, Update, UpdateProgress, UpdateThumbnail,
+    self, AddRecent, Delete, DeleteThumbnail, Filter, Get, GetProgress, List, ListByCategory,
+    ListRecentlyAdded, ListRecentlyRead, Sort, Update, UpdateProgress, UpdateThumbnail,
 };
+use db::publication_tag;
+use db::search::{Search, SearchContent};
+use fs::cache::Cache;
 use fs::executor::{DeleteFile, FsExecutor};
+use fs::page::{OutputFormat, PageImageError, PageTransform};
 use fs::thumbnail;
 use futures::{future, Future, IntoFuture, Stream};
 use mime;
-use models::{NewPublication, Publication, PublicationProgress, CBR, CBZ, EPUB};
-use reader::{comic, epub};
+use models::{
+    NewPublication, Publication, PublicationTag, TaggedPublication, CB7, CBR, CBT, CBZ, EPUB,
+    FOLDER, PDF,
+};
+use reader::executor::{ListPages, ReadPage};
+use reader::{comic, epub, pdf};
 use state::AppState;
 use std::{
     error::Error,
@@ -26,10 +36,15 @@ use std::{
 };
 
 pub const BASE_PATH: &str = "/api/publication";
+/// Pages are only shown to logged in users, but never change for a given ETag.
+const PAGE_CACHE_CONTROL: &str = "private, max-age=604800";
+const DEFAULT_PER_PAGE: i64 = 50;
+const MAX_PER_PAGE: i64 = 200;
 
 #[derive(Debug)]
 enum PublicationError {
     InvalidMediaFormat,
+    EmptySearchQuery,
 }
 
 impl Error for PublicationError {
@@ -44,16 +59,122 @@ impl Display for PublicationError {
     }
 }
 
-fn list(state: State<AppState>) -> FutureResponse<HttpResponse> {
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    pub sort: Option<Sort>,
+    pub media_format: Option<String>,
+    pub media_type_id: Option<i32>,
+    pub author_id: Option<i32>,
+    pub tag_id: Option<i32>,
+    pub missing: Option<bool>,
+}
+
+impl ListQuery {
+    fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    fn per_page(&self) -> i64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .max(1)
+            .min(MAX_PER_PAGE)
+    }
+
+    fn filter(self) -> Filter {
+        Filter {
+            media_format: self.media_format,
+            media_type_id: self.media_type_id,
+            author_id: self.author_id,
+            tag_id: self.tag_id,
+            missing: self.missing,
+        }
+    }
+}
+
+fn list(
+    state: State<AppState>,
+    query: Query<ListQuery>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
+    let query = query.into_inner();
+    state
+        .db
+        .send(List {
+            user_id: current_user.0.id,
+            page: query.page(),
+            per_page: query.per_page(),
+            sort: query.sort.unwrap_or_default(),
+            filter: query.filter(),
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|publications| Ok(HttpResponse::Ok().json(publications)))
+        .responder()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+fn search(state: State<AppState>, query: Query<SearchQuery>) -> FutureResponse<HttpResponse> {
+    let query = query.into_inner();
+    if query.q.trim().is_empty() {
+        return Box::new(future::err(ErrorBadRequest(
+            PublicationError::EmptySearchQuery,
+        )));
+    }
+
     state
         .db
-        .send(List {})
+        .send(Search {
+            query: query.q,
+            page: query.page.unwrap_or(1).max(1),
+            per_page: query
+                .per_page
+                .unwrap_or(DEFAULT_PER_PAGE)
+                .max(1)
+                .min(MAX_PER_PAGE),
+        })
         .from_err()
         .and_then(|res| res)
         .and_then(|publications| Ok(HttpResponse::Ok().json(publications)))
         .responder()
 }
 
+fn search_content(
+    state: State<AppState>,
+    query: Query<SearchQuery>,
+) -> FutureResponse<HttpResponse> {
+    let query = query.into_inner();
+    if query.q.trim().is_empty() {
+        return Box::new(future::err(ErrorBadRequest(
+            PublicationError::EmptySearchQuery,
+        )));
+    }
+
+    state
+        .db
+        .send(SearchContent {
+            query: query.q,
+            page: query.page.unwrap_or(1).max(1),
+            per_page: query
+                .per_page
+                .unwrap_or(DEFAULT_PER_PAGE)
+                .max(1)
+                .min(MAX_PER_PAGE),
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|matches| Ok(HttpResponse::Ok().json(matches)))
+        .responder()
+}
+
 fn create(state: State<AppState>, json: Json<NewPublication>) -> FutureResponse<HttpResponse> {
     state
         .db
@@ -76,14 +197,22 @@ fn update(state: State<AppState>, json: Json<Publication>) -> FutureResponse<Htt
         .responder()
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ProgressUpdate {
+    pub publication_id: i32,
+    pub progress: f32,
+}
+
 fn update_progress(
     state: State<AppState>,
-    json: Json<PublicationProgress>,
+    json: Json<ProgressUpdate>,
+    current_user: CurrentUser,
 ) -> FutureResponse<HttpResponse> {
     let json = json.into_inner();
     state
         .db
         .send(UpdateProgress {
+            user_id: current_user.0.id,
             publication_id: json.publication_id,
             progress: json.progress,
         })
@@ -93,10 +222,15 @@ fn update_progress(
         .responder()
 }
 
-fn get_progress(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+fn get_progress(
+    state: State<AppState>,
+    publication_id: Path<i32>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
     state
         .db
         .send(GetProgress {
+            user_id: current_user.0.id,
             publication_id: publication_id.into_inner(),
         })
         .from_err()
@@ -105,114 +239,390 @@ fn get_progress(state: State<AppState>, publication_id: Path<i32>) -> FutureResp
         .responder()
 }
 
-fn delete(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+fn delete(
+    state: State<AppState>,
+    publication_id: Path<i32>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    let publication_id = publication_id.into_inner();
+    let cache = state.cache.clone();
+    state
+        .db
+        .send(Delete { publication_id })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(_) => {
+                if let Err(err) = cache.remove(publication_id) {
+                    println!("Unable to clear the cache of {}: {}", publication_id, err);
+                }
+                Ok(HttpResponse::Ok().json(()))
+            }
+            Err(_err) => Ok(HttpResponse::InternalServerError().into()),
+        })
+        .responder()
+}
+
+fn list_duplicates(state: State<AppState>, _admin: Admin) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(duplicate::List {})
+        .from_err()
+        .and_then(|res| Ok(HttpResponse::Ok().json(res?)))
+        .responder()
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    duplicate_ids: Vec<i32>,
+}
+
+/// Merge the given duplicates into the publication and delete them, their files stay on disk.
+fn merge(
+    state: State<AppState>,
+    publication_id: Path<i32>,
+    json: Json<MergeRequest>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
     state
         .db
-        .send(Delete {
+        .send(duplicate::Merge {
             publication_id: publication_id.into_inner(),
+            duplicate_ids: json.into_inner().duplicate_ids,
         })
         .from_err()
-        .and_then(|res| match res {
-            Ok(_) => Ok(HttpResponse::Ok().json(())),
-            Err(_err) => Ok(HttpResponse::InternalServerError().into()),
-        })
+        .and_then(|res| Ok(HttpResponse::Ok().json(res?)))
         .responder()
 }
 
 fn get(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+    let publication_id = publication_id.into_inner();
     state
         .db
-        .send(Get {
+        .send(Get { publication_id })
+        .join(state.db.send(publication_tag::List { publication_id }))
+        .from_err()
+        .and_then(|(publication, tags)| {
+            Ok(TaggedPublication {
+                publication: publication?,
+                tags: tags?,
+            })
+        })
+        .map(|publication| HttpResponse::Ok().json(publication))
+        .responder()
+}
+
+fn list_tags(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(publication_tag::List {
             publication_id: publication_id.into_inner(),
         })
         .from_err()
         .and_then(|res| res)
-        .map(|publication| HttpResponse::Ok().json(publication))
+        .and_then(|tags| Ok(HttpResponse::Ok().json(tags)))
         .responder()
 }
 
-fn read(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
-    let config = state.config.clone();
+fn add_tag(state: State<AppState>, path: Path<(i32, i32)>) -> FutureResponse<HttpResponse> {
+    let (publication_id, tag_id) = path.into_inner();
+    state
+        .db
+        .send(publication_tag::Create(PublicationTag {
+            publication_id,
+            tag_id,
+        }))
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn remove_tag(state: State<AppState>, path: Path<(i32, i32)>) -> FutureResponse<HttpResponse> {
+    let (publication_id, tag_id) = path.into_inner();
+    state
+        .db
+        .send(publication_tag::Delete(PublicationTag {
+            publication_id,
+            tag_id,
+        }))
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn add_tags(
+    state: State<AppState>,
+    json: Json<publication_tag::CreateBatch>,
+) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(json.into_inner())
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn list_by_tag(
+    state: State<AppState>,
+    tag_id: Path<i32>,
+    query: Query<ListQuery>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
+    let query = query.into_inner();
+    state
+        .db
+        .send(List {
+            user_id: current_user.0.id,
+            page: query.page(),
+            per_page: query.per_page(),
+            sort: query.sort.unwrap_or_default(),
+            filter: Filter {
+                tag_id: Some(tag_id.into_inner()),
+                ..query.filter()
+            },
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|publications| Ok(HttpResponse::Ok().json(publications)))
+        .responder()
+}
+
+fn read(
+    state: State<AppState>,
+    publication_id: Path<i32>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
     let publication_id = publication_id.into_inner();
     let db = &state.db;
     db.send(Get {
         publication_id: publication_id,
     })
-    .join(db.send(AddRecent(publication_id)))
+    .join(db.send(AddRecent {
+        user_id: current_user.0.id,
+        publication_id,
+    }))
     .from_err()
     .and_then(|res| res)
     .and_then(
         move |(publication, _)| match publication.media_format.as_ref() {
-            CBR => read_cbr(&config, &publication),
-            CBZ => read_cbz(&config, &publication),
+            CBR | CBZ | CB7 | CBT | FOLDER => read_comic(&publication),
             EPUB => read_epub(&publication),
+            PDF => read_pdf(&publication),
             _ => Ok(HttpResponse::InternalServerError().into()),
         },
     )
     .responder()
 }
 
-fn read_cbz(config: &Config, publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
-    comic::open(config, &publication)
+fn read_comic(publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
+    comic::open(&publication)
         .map_err(|err| err.into())
         .and_then(|data| Ok(HttpResponse::Ok().json(data)))
 }
 
-fn read_cbr(config: &Config, publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
-    comic::open(config, &publication)
+fn read_epub(publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
+    epub::open(&publication)
         .map_err(|err| err.into())
         .and_then(|data| Ok(HttpResponse::Ok().json(data)))
 }
 
-fn read_epub(publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
-    epub::open(&publication)
+fn read_pdf(publication: &Publication) -> Result<HttpResponse, actix_web::Error> {
+    pdf::open(&publication)
         .map_err(|err| err.into())
         .and_then(|data| Ok(HttpResponse::Ok().json(data)))
 }
 
-fn read_page(state: State<AppState>, params: Path<(i32, usize)>) -> FutureResponse<NamedFile> {
-    let publication_id = params.0;
-    let config = state.config.clone();
-    let db = &state.db;
+/// Pages of a comic in reading order with their dimensions, page numbers index into this list.
+fn list_pages(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+    let cache = state.cache.clone();
+    let reader = state.reader.clone();
+    state
+        .db
+        .send(Get {
+            publication_id: publication_id.into_inner(),
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(move |publication| match publication.media_format.as_ref() {
+            CBR | CBZ | CB7 | CBT | FOLDER => future::Either::A(
+                reader
+                    .send(ListPages { cache, publication })
+                    .from_err()
+                    .and_then(|res| res.map_err(|err| err.into()))
+                    .map(|pages| HttpResponse::Ok().json(pages)),
+            ),
+            _ => future::Either::B(future::err(ErrorBadRequest(
+                PublicationError::InvalidMediaFormat,
+            ))),
+        })
+        .responder()
+}
 
-    db.send(Get {
-        publication_id: publication_id,
-    })
-    .from_err()
-    .and_then(|res| res)
-    .and_then(move |publication| match publication.media_format.as_ref() {
-        CBR => read_page_comic(&config, &publication, params.1),
-        CBZ => read_page_comic(&config, &publication, params.1),
-        _ => Err(ErrorBadRequest(PublicationError::InvalidMediaFormat)),
-    })
-    .map_err(|err| {
-        println!("Error: {:?}", err);
-        err
-    })
-    .responder()
+/// Chapters of an EPUB as a tree, each entry points to a page and the element to scroll to.
+fn read_toc(state: State<AppState>, publication_id: Path<i32>) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(Get {
+            publication_id: publication_id.into_inner(),
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|publication| match publication.media_format.as_ref() {
+            EPUB => epub::toc(&publication)
+                .map_err(|err| err.into())
+                .map(|toc| HttpResponse::Ok().json(toc)),
+            _ => Err(ErrorBadRequest(PublicationError::InvalidMediaFormat)),
+        })
+        .responder()
 }
 
-fn read_page_comic(
-    config: &Config,
-    publication: &Publication,
-    page_num: usize,
-) -> Result<NamedFile, actix_web::Error> {
-    let filename = comic::page(config, &publication, page_num).expect("Unable to read page");
-    let file = NamedFile::open(filename);
-    file.map_err(|err| {
-        println!("File Error: {:?}", err);
-        err.into()
-    })
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub quality: Option<u8>,
+    /// `jpg`, `png` or `webp`.
+    pub format: Option<String>,
+}
+
+impl PageQuery {
+    fn transform(&self) -> std::result::Result<PageTransform, PageImageError> {
+        let format = match self.format {
+            Some(ref format) => Some(OutputFormat::parse(format)?),
+            None => None,
+        };
+        Ok(PageTransform {
+            width: self.width,
+            height: self.height,
+            quality: self.quality,
+            format,
+        })
+    }
+}
+
+/// Page of a comic or PDF. Comic pages can be resized and transcoded through the query, pages
+/// don't change as long as the file doesn't so they come with an ETag and can be cached by the
+/// browser. A page that couldn't be transformed is served as it is, without either.
+fn read_page(
+    req: HttpRequest<AppState>,
+    params: Path<(i32, usize)>,
+    query: Query<PageQuery>,
+) -> FutureResponse<HttpResponse> {
+    let (publication_id, page_number) = params.into_inner();
+    let transform = match query.transform() {
+        Ok(transform) => transform,
+        Err(err) => return Box::new(future::ok(HttpResponse::BadRequest().body(err.to_string()))),
+    };
+    let cache = req.state().cache.clone();
+    let reader = req.state().reader.clone();
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    req.state()
+        .db
+        .send(Get {
+            publication_id: publication_id,
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(move |publication| {
+            let etag = page_etag(&publication, page_number, &transform);
+            if let Some(ref if_none_match) = if_none_match {
+                let matches = if_none_match
+                    .split(',')
+                    .any(|tag| tag.trim() == etag || tag.trim() == "*");
+                if matches {
+                    return future::Either::A(future::ok(
+                        HttpResponse::NotModified()
+                            .header(header::ETAG, etag)
+                            .header(header::CACHE_CONTROL, PAGE_CACHE_CONTROL)
+                            .finish(),
+                    ));
+                }
+            }
+            match publication.media_format.as_ref() {
+                CBR | CBZ | CB7 | CBT | FOLDER | PDF => {}
+                _ => {
+                    return future::Either::A(future::err(ErrorBadRequest(
+                        PublicationError::InvalidMediaFormat,
+                    )))
+                }
+            }
+
+            let page = reader
+                .send(ReadPage {
+                    cache,
+                    publication,
+                    page_number,
+                    transform,
+                })
+                .from_err()
+                .and_then(|res| res)
+                .and_then(move |(page, transformed)| {
+                    let content_type = page
+                        .extension()
+                        .and_then(|extension| extension.to_str())
+                        .map(file_extension_to_mime)
+                        .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+                    let content = fs::read(&page)?;
+                    let mut response = HttpResponse::Ok();
+                    response.content_type(content_type.to_string());
+                    // the ETag stands for the transformed page, the untransformed one shouldn't
+                    // be cached under it
+                    if transformed {
+                        response
+                            .header(header::ETAG, etag)
+                            .header(header::CACHE_CONTROL, PAGE_CACHE_CONTROL);
+                    }
+                    Ok(response.body(content))
+                });
+            future::Either::B(page)
+        })
+        .map_err(|err| {
+            println!("Error: {:?}", err);
+            err
+        })
+        .responder()
+}
+
+/// Changes whenever the file of the publication does, or a different rendition of the page is
+/// asked for.
+fn page_etag(publication: &Publication, page_number: usize, transform: &PageTransform) -> String {
+    let version = match (&publication.file_hash, publication.file_modified) {
+        (Some(hash), _) => hash.chars().take(16).collect(),
+        (None, Some(modified)) => modified.timestamp().to_string(),
+        (None, None) => "0".to_string(),
+    };
+    format!(
+        "\"{}-{}-{}-{}\"",
+        publication.id,
+        page_number,
+        transform.key(),
+        version
+    )
 }
 
 fn list_by_category(
     state: State<AppState>,
     category_id: Path<i32>,
+    query: Query<ListQuery>,
+    current_user: CurrentUser,
 ) -> FutureResponse<HttpResponse> {
+    let query = query.into_inner();
     state
         .db
         .send(ListByCategory {
+            user_id: current_user.0.id,
             category_id: category_id.into_inner(),
+            page: query.page(),
+            per_page: query.per_page(),
+            sort: query.sort.unwrap_or_default(),
+            filter: query.filter(),
         })
         .from_err()
         .and_then(|res| res)
@@ -234,10 +644,15 @@ fn list_recently_added(
         .responder()
 }
 
-fn list_recently_read(state: State<AppState>, count: Path<i64>) -> FutureResponse<HttpResponse> {
+fn list_recently_read(
+    state: State<AppState>,
+    count: Path<i64>,
+    current_user: CurrentUser,
+) -> FutureResponse<HttpResponse> {
     state
         .db
         .send(ListRecentlyRead {
+            user_id: current_user.0.id,
             count: count.into_inner(),
         })
         .from_err()
@@ -269,23 +684,19 @@ fn download(req: &HttpRequest<AppState>) -> FutureResponse<NamedFile> {
     let publication_id: i32 = req.match_info().query("publication_id").unwrap();
     let file: PathBuf = req.match_info().query("tail").unwrap();
     let state: &AppState = req.state();
-    let config = state.config.clone();
+    let cache = state.cache.clone();
     req.state()
         .db
         .send(Get { publication_id })
         .from_err()
         .and_then(|res| res)
-        .and_then(move |publication| download_file(&config, &publication, file))
+        .and_then(move |publication| download_file(&cache, &publication, file))
         .responder()
 }
 
-fn download_file(
-    config: &Config,
-    the_publication: &Publication,
-    path: PathBuf,
-) -> Result<NamedFile> {
+fn download_file(cache: &Cache, the_publication: &Publication, path: PathBuf) -> Result<NamedFile> {
     if the_publication.media_format == EPUB {
-        return epub::file(config, the_publication, path).map_err(|err| err.into());
+        return epub::file(cache, the_publication, path).map_err(|err| err.into());
     }
 
     Err(ErrorBadRequest(PublicationError::InvalidMediaFormat))
@@ -409,6 +820,7 @@ fn upload(req: HttpRequest<AppState>, publication_id: Path<i32>) -> FutureRespon
 fn delete_thumbnail(
     req: HttpRequest<AppState>,
     publication_id: Path<i32>,
+    _admin: Admin,
 ) -> FutureResponse<HttpResponse> {
     let state = req.state();
     let db = state.db.clone();
@@ -454,14 +866,24 @@ fn delete_thumbnail_file(
 pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
     App::with_state(state)
         .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
         .prefix(prefix)
+        .route("/search", Method::GET, search)
+        .route("/search/content", Method::GET, search_content)
+        .route("/duplicates", Method::GET, list_duplicates)
+        .route("/tag", Method::POST, add_tags)
+        .route("/tag/{tag_id}", Method::GET, list_by_tag)
         .route("/{publication_id}", Method::GET, get)
         .route("/", Method::GET, list)
         .route("/", Method::POST, create)
         .route("/", Method::PUT, update)
         .route("/{publication_id}", Method::DELETE, delete)
+        .route("/{publication_id}/merge", Method::POST, merge)
         .route("/{publication_id}", Method::GET, get)
         .route("/category/{category_id}", Method::GET, list_by_category)
+        .route("/{publication_id}/tag", Method::GET, list_tags)
+        .route("/{publication_id}/tag/{tag_id}", Method::PUT, add_tag)
+        .route("/{publication_id}/tag/{tag_id}", Method::DELETE, remove_tag)
         .route(
             "/recently_added/category_id/{category_id}/count/{count}",
             Method::GET,
@@ -478,6 +900,8 @@ pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
             generate_thumbnail,
         )
         .route("/read/{publication_id}", Method::GET, read)
+        .route("/read/{publication_id}/pages", Method::GET, list_pages)
+        .route("/read/{publication_id}/toc", Method::GET, read_toc)
         .route(
             "/read/{publication_id}/page/{page_number}",
             Method::GET,
@@ -0,0 +1,72 @@
+use actix::Arbiter;
+use actix_web::http::{header, Method};
+use actix_web::{error, middleware, App, HttpResponse, State};
+use api::auth::{Admin, Authenticate};
+use bytes::Bytes;
+use futures::{Future, Stream};
+use scan::options::ScanOptions;
+use scan::pipeline;
+use scan::progress::ScanStatus;
+use serde_json;
+use state::AppState;
+use std::time::{Duration, Instant};
+use tokio::timer::Interval;
+
+/// How often the event stream checks the scan progress for changes.
+const EVENT_INTERVAL_MILLIS: u64 = 500;
+
+fn start(state: State<AppState>, _admin: Admin) -> HttpResponse {
+    let task = pipeline::run(
+        state.config.clone(),
+        state.db.clone(),
+        state.scanner.clone(),
+        state.progress.clone(),
+        state.cache.clone(),
+        ScanOptions::default(),
+    );
+    match task {
+        Ok(task) => {
+            Arbiter::spawn(task.map_err(|err| println!("Scan failed: {}", err)));
+            HttpResponse::Accepted().json(state.progress.status())
+        }
+        Err(_) => HttpResponse::Conflict().json(state.progress.status()),
+    }
+}
+
+fn status(state: State<AppState>) -> HttpResponse {
+    HttpResponse::Ok().json(state.progress.status())
+}
+
+/// Server-Sent Events stream with the scan status, sent whenever it changes.
+fn events(state: State<AppState>) -> HttpResponse {
+    let progress = state.progress.clone();
+    let mut last_status: Option<ScanStatus> = None;
+    let stream = Interval::new(Instant::now(), Duration::from_millis(EVENT_INTERVAL_MILLIS))
+        .map_err(error::ErrorInternalServerError)
+        .map(move |_| progress.status())
+        .filter(move |status| {
+            let changed = last_status.as_ref() != Some(status);
+            last_status = Some(status.clone());
+            changed
+        })
+        .and_then(|status| {
+            serde_json::to_string(&status)
+                .map(|json| Bytes::from(format!("data: {}\n\n", json)))
+                .map_err(error::ErrorInternalServerError)
+        });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .streaming(stream)
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
+        .prefix(prefix)
+        .route("/", Method::POST, start)
+        .route("/status", Method::GET, status)
+        .route("/events", Method::GET, events)
+}
@@ -0,0 +1,177 @@
+use actix_web::http::Method;
+use actix_web::{
+    error, middleware, App, AsyncResponder, FutureResponse, HttpResponse, Json, State,
+};
+use api::auth::{Admin, Authenticate};
+use db::library::{self, LibraryData, Replace};
+use db::media_type_hint;
+use db::setting::{Get, Update};
+use futures::{future, Future};
+use models::{Library, MediaTypeHint, NewMediaTypeHint, Setting};
+use scan::watch::ReloadLibraries;
+use state::AppState;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize)]
+struct SettingResponse {
+    #[serde(flatten)]
+    setting: Setting,
+    libraries: Vec<Library>,
+    media_type_hints: Vec<MediaTypeHint>,
+}
+
+/// Settings that are left out stay as they are.
+#[derive(Debug, Deserialize)]
+pub struct SettingUpdate {
+    pub min_category_score: Option<f64>,
+    pub libraries: Option<Vec<LibraryData>>,
+    pub media_type_hints: Option<Vec<NewMediaTypeHint>>,
+}
+
+fn get(state: State<AppState>, _admin: Admin) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(Get {})
+        .join3(
+            state.db.send(library::List {}),
+            state.db.send(media_type_hint::List {}),
+        )
+        .from_err()
+        .and_then(|(setting, libraries, media_type_hints)| {
+            Ok(HttpResponse::Ok().json(SettingResponse {
+                setting: setting?,
+                libraries: libraries?,
+                media_type_hints: media_type_hints?,
+            }))
+        })
+        .responder()
+}
+
+fn update(
+    state: State<AppState>,
+    json: Json<SettingUpdate>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    let SettingUpdate {
+        min_category_score,
+        libraries,
+        media_type_hints,
+    } = json.into_inner();
+    if let Some(score) = min_category_score {
+        if score < 0.0 || score > 1.0 {
+            return Box::new(future::err(error::ErrorBadRequest(
+                "min_category_score must be between 0 and 1",
+            )));
+        }
+    }
+    if let Some(data) = libraries
+        .iter()
+        .flat_map(|libraries| libraries.iter())
+        .find(|data| !Path::new(&data.path).is_dir())
+    {
+        return Box::new(future::err(error::ErrorBadRequest(format!(
+            "{} is not a folder",
+            data.path
+        ))));
+    }
+    if let Some(libraries) = libraries.as_ref() {
+        if let Err(err) = check_overlap(libraries) {
+            return Box::new(future::err(err));
+        }
+    }
+
+    if media_type_hints
+        .iter()
+        .flat_map(|hints| hints.iter())
+        .any(|hint| hint.folder.trim().is_empty())
+    {
+        return Box::new(future::err(error::ErrorBadRequest(
+            "media type hints need a folder name",
+        )));
+    }
+
+    let db = state.db.clone();
+    let watch = match libraries {
+        Some(_) => state.watch.clone(),
+        None => None,
+    };
+    let replace_libraries = match libraries {
+        Some(libraries) => future::Either::A(
+            state
+                .db
+                .send(Replace { libraries })
+                .map(|res| res.map(|_| ())),
+        ),
+        None => future::Either::B(future::ok(Ok(()))),
+    };
+    let replace_hints = match media_type_hints {
+        Some(hints) => future::Either::A(
+            state
+                .db
+                .send(media_type_hint::Replace { hints })
+                .map(|res| res.map(|_| ())),
+        ),
+        None => future::Either::B(future::ok(Ok(()))),
+    };
+    state
+        .db
+        .send(Update { min_category_score })
+        .join3(replace_libraries, replace_hints)
+        .from_err()
+        .and_then(|(setting, libraries, hints)| {
+            libraries?;
+            hints?;
+            if let Some(watch) = watch {
+                watch.do_send(ReloadLibraries);
+            }
+            setting
+        })
+        .and_then(move |setting| {
+            db.send(library::List {})
+                .join(db.send(media_type_hint::List {}))
+                .from_err()
+                .and_then(|(libraries, media_type_hints)| {
+                    Ok(HttpResponse::Ok().json(SettingResponse {
+                        setting,
+                        libraries: libraries?,
+                        media_type_hints: media_type_hints?,
+                    }))
+                })
+        })
+        .responder()
+}
+
+/// Libraries can't share files, otherwise publications would be imported twice and moved back
+/// and forth between them by every scan. Symlinks are resolved first, so the same folder can't
+/// sneak in under another name.
+fn check_overlap(libraries: &[LibraryData]) -> Result<(), actix_web::Error> {
+    let paths = libraries
+        .iter()
+        .map(|data| {
+            Path::new(&data.path)
+                .canonicalize()
+                .map(|path| (data, path))
+                .map_err(|err| error::ErrorBadRequest(format!("{}: {}", data.path, err)))
+        })
+        .collect::<Result<Vec<(&LibraryData, PathBuf)>, actix_web::Error>>()?;
+    for (i, &(data, ref path)) in paths.iter().enumerate() {
+        for &(other, ref other_path) in paths.iter().skip(i + 1) {
+            if path.starts_with(other_path) || other_path.starts_with(path) {
+                return Err(error::ErrorBadRequest(format!(
+                    "{} and {} overlap",
+                    data.path, other.path
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
+        .prefix(prefix)
+        .route("/", Method::GET, get)
+        .route("/", Method::PUT, update)
+}
@@ -0,0 +1,85 @@
+use actix_web::http::Method;
+use actix_web::{middleware, App, AsyncResponder, FutureResponse, HttpResponse, Json, Path, State};
+use api::auth::{Admin, Authenticate, CurrentUser};
+use db::user::{Create, Delete, List};
+use futures::Future;
+use password::HashPassword;
+use state::AppState;
+
+fn list(state: State<AppState>, _admin: Admin) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(List {})
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|users| Ok(HttpResponse::Ok().json(users)))
+        .responder()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserData {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+fn create(
+    state: State<AppState>,
+    json: Json<UserData>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    let UserData {
+        username,
+        password,
+        is_admin,
+    } = json.into_inner();
+    let db = state.db.clone();
+    state
+        .password
+        .send(HashPassword { password })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(move |password_hash| {
+            db.send(Create {
+                username,
+                password_hash,
+                is_admin,
+            })
+            .from_err()
+        })
+        .and_then(|res| res)
+        .and_then(|user| Ok(HttpResponse::Ok().json(user)))
+        .responder()
+}
+
+fn delete(
+    state: State<AppState>,
+    user_id: Path<i32>,
+    _admin: Admin,
+) -> FutureResponse<HttpResponse> {
+    state
+        .db
+        .send(Delete {
+            user_id: user_id.into_inner(),
+        })
+        .from_err()
+        .and_then(|res| res)
+        .and_then(|_| Ok(HttpResponse::Ok().json(())))
+        .responder()
+}
+
+fn me(current_user: CurrentUser) -> HttpResponse {
+    HttpResponse::Ok().json(current_user.0)
+}
+
+pub fn create_app(state: AppState, prefix: &str) -> App<AppState> {
+    App::with_state(state)
+        .middleware(middleware::Logger::default())
+        .middleware(Authenticate)
+        .prefix(prefix)
+        .route("/", Method::GET, list)
+        .route("/", Method::POST, create)
+        .route("/me", Method::GET, me)
+        .route("/{user_id}", Method::DELETE, delete)
+}
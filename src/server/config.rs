@@ -1,10 +1,18 @@
 use dotenv::dotenv;
 use std::env;
 
+/// Default limit of the extraction cache, in megabytes.
+const DEFAULT_CACHE_SIZE: u64 = 1024;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database: String,
     pub pustaka_home: String,
+    pub admin_username: String,
+    pub admin_password: Option<String>,
+    pub watch: bool,
+    /// Limit of the extraction cache in bytes.
+    pub cache_size: u64,
 }
 
 pub fn get_config() -> Config {
@@ -12,5 +20,16 @@ pub fn get_config() -> Config {
     Config {
         database: env::var("PUSTAKA_DATABASE_URL").unwrap_or("pustaka.db".to_string()),
         pustaka_home: env::var("PUSTAKA_HOME").unwrap_or("".to_string()),
+        admin_username: env::var("PUSTAKA_ADMIN_USERNAME").unwrap_or("admin".to_string()),
+        admin_password: env::var("PUSTAKA_ADMIN_PASSWORD").ok(),
+        watch: env::var("PUSTAKA_WATCH")
+            .map(|watch| watch == "true" || watch == "1")
+            .unwrap_or(false),
+        cache_size: env::var("PUSTAKA_CACHE_SIZE")
+            .ok()
+            .and_then(|size| size.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_CACHE_SIZE)
+            * 1024
+            * 1024,
     }
 }
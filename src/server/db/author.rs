@@ -106,3 +106,38 @@ impl Handler<Get> for DbExecutor {
         }
     }
 }
+
+pub struct GetOrCreate {
+    pub name: String,
+}
+impl Message for GetOrCreate {
+    type Result = Result<Author, Error>;
+}
+impl Handler<GetOrCreate> for DbExecutor {
+    type Result = Result<Author, Error>;
+
+    fn handle(&mut self, msg: GetOrCreate, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let existing_author = author
+            .filter(name.eq(&msg.name))
+            .first::<Author>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        match existing_author {
+            Some(the_author) => Ok(the_author),
+            None => {
+                diesel::insert_into(author)
+                    .values(NewAuthor {
+                        name: msg.name.clone(),
+                    })
+                    .execute(&*connection)
+                    .map_err(actix_web::error::ErrorInternalServerError)?;
+                author
+                    .filter(name.eq(&msg.name))
+                    .first::<Author>(&*connection)
+                    .map_err(actix_web::error::ErrorInternalServerError)
+            }
+        }
+    }
+}
@@ -5,21 +5,24 @@ use diesel::prelude::*;
 
 use actix::prelude::*;
 use db::executor::DbExecutor;
-use models::{Category, FavoriteCategory, NewCategory};
+use models::{Category, FavoriteCategory, NewCategory, UserId};
 use schema::category::dsl::*;
 
-pub struct Favorite {}
+pub struct Favorite {
+    pub user_id: UserId,
+}
 impl Message for Favorite {
     type Result = Result<Vec<Category>, Error>;
 }
 impl Handler<Favorite> for DbExecutor {
     type Result = Result<Vec<Category>, Error>;
 
-    fn handle(&mut self, _msg: Favorite, _: &mut Self::Context) -> Self::Result {
-        use schema::favorite_category::dsl::favorite_category;
+    fn handle(&mut self, msg: Favorite, _: &mut Self::Context) -> Self::Result {
+        use schema::favorite_category::dsl::{favorite_category, user_id};
         let connection: &SqliteConnection = &self.0.get().unwrap();
 
         let favorite_category_ids: Vec<i32> = favorite_category
+            .filter(user_id.eq(msg.user_id))
             .load::<FavoriteCategory>(&*connection)
             .expect("Error loading favorite categories")
             .iter()
@@ -33,6 +36,45 @@ impl Handler<Favorite> for DbExecutor {
     }
 }
 
+pub struct AddFavorite(pub FavoriteCategory);
+impl Message for AddFavorite {
+    type Result = Result<(), Error>;
+}
+impl Handler<AddFavorite> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: AddFavorite, _: &mut Self::Context) -> Self::Result {
+        use schema::favorite_category::dsl::favorite_category;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::replace_into(favorite_category)
+            .values(msg.0)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
+
+pub struct RemoveFavorite(pub FavoriteCategory);
+impl Message for RemoveFavorite {
+    type Result = Result<(), Error>;
+}
+impl Handler<RemoveFavorite> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: RemoveFavorite, _: &mut Self::Context) -> Self::Result {
+        use schema::favorite_category::dsl::{category_id, favorite_category, user_id};
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::delete(
+            favorite_category
+                .filter(user_id.eq(msg.0.user_id))
+                .filter(category_id.eq(msg.0.category_id)),
+        )
+        .execute(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
+
 pub struct List {}
 impl Message for List {
     type Result = Result<Vec<Category>, Error>;
@@ -49,6 +91,42 @@ impl Handler<List> for DbExecutor {
     }
 }
 
+pub struct GetOrCreate {
+    pub name: String,
+}
+impl Message for GetOrCreate {
+    type Result = Result<Category, Error>;
+}
+impl Handler<GetOrCreate> for DbExecutor {
+    type Result = Result<Category, Error>;
+
+    fn handle(&mut self, msg: GetOrCreate, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let existing_category = category
+            .filter(name.eq(&msg.name))
+            .first::<Category>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        match existing_category {
+            Some(the_category) => Ok(the_category),
+            None => {
+                diesel::insert_into(category)
+                    .values(NewCategory {
+                        name: msg.name.clone(),
+                        parent_id: None,
+                    })
+                    .execute(&*connection)
+                    .map_err(actix_web::error::ErrorInternalServerError)?;
+                category
+                    .filter(name.eq(&msg.name))
+                    .first::<Category>(&*connection)
+                    .map_err(actix_web::error::ErrorInternalServerError)
+            }
+        }
+    }
+}
+
 pub struct Create {
     pub new_category: NewCategory,
 }
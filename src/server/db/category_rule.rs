@@ -0,0 +1,82 @@
+extern crate diesel;
+
+use actix_web::Error;
+use diesel::prelude::*;
+
+use actix::prelude::*;
+use db::executor::DbExecutor;
+use models::{CategoryRule, NewCategoryRule};
+use schema::category_rule::dsl::*;
+
+/// All rules, in the order the scanner checks them.
+pub struct List {}
+impl Message for List {
+    type Result = Result<Vec<CategoryRule>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<CategoryRule>, Error>;
+
+    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        category_rule
+            .order_by((priority.desc(), id.asc()))
+            .load::<CategoryRule>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+pub struct Create {
+    pub new_rule: NewCategoryRule,
+}
+impl Message for Create {
+    type Result = Result<(), Error>;
+}
+impl Handler<Create> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Create, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::insert_into(category_rule)
+            .values(msg.new_rule)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorBadRequest)?;
+        Ok(())
+    }
+}
+
+pub struct Update {
+    pub rule: CategoryRule,
+}
+impl Message for Update {
+    type Result = Result<(), Error>;
+}
+impl Handler<Update> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Update, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::update(category_rule.filter(id.eq(msg.rule.id)))
+            .set(msg.rule)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorBadRequest)?;
+        Ok(())
+    }
+}
+
+pub struct Delete {
+    pub rule_id: i32,
+}
+impl Message for Delete {
+    type Result = Result<(), Error>;
+}
+impl Handler<Delete> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::delete(category_rule.filter(id.eq(msg.rule_id)))
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
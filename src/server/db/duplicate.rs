@@ -0,0 +1,351 @@
+extern crate diesel;
+
+use actix::prelude::*;
+use actix_web::Error;
+use db::executor::DbExecutor;
+use diesel::prelude::*;
+use fs::thumbnail::hash_distance;
+use models::{
+    IgnoredFile, Publication, PublicationCategory, PublicationId, PublicationProgress,
+    PublicationTag, RecentPublication,
+};
+use schema::publication::dsl::*;
+use std::collections::HashMap;
+
+/// Thumbnails whose perceptual hashes are at most this many bits apart are considered the same
+/// cover.
+const MAX_THUMBNAIL_DISTANCE: u32 = 6;
+/// Number of bands the hashes are split into to find the close ones.
+const BANDS: u32 = MAX_THUMBNAIL_DISTANCE + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    /// The files have the same content.
+    Exact,
+    /// The files differ but their covers look alike, e.g. the same book in two formats.
+    Probable,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    pub publications: Vec<Publication>,
+}
+
+/// Publications that are likely to be the same book. Missing publications are left out.
+#[derive(Debug)]
+pub struct List {}
+impl Message for List {
+    type Result = Result<Vec<DuplicateGroup>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<DuplicateGroup>, Error>;
+
+    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let publications = publication
+            .filter(missing.eq(false))
+            .order_by(id.asc())
+            .load::<Publication>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        let mut groups = exact_duplicates(&publications);
+        groups.append(&mut probable_duplicates(&publications));
+        Ok(groups)
+    }
+}
+
+fn exact_duplicates(publications: &[Publication]) -> Vec<DuplicateGroup> {
+    let mut by_hash: HashMap<&str, Vec<&Publication>> = HashMap::new();
+    for the_publication in publications.iter() {
+        if let Some(ref hash) = the_publication.file_hash {
+            by_hash
+                .entry(hash.as_str())
+                .or_default()
+                .push(the_publication);
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(_, group)| DuplicateGroup {
+            kind: DuplicateKind::Exact,
+            publications: group.into_iter().cloned().collect(),
+        })
+        .collect();
+    groups.sort_by_key(|group| group.publications[0].id);
+    groups
+}
+
+/// Cluster the publications whose thumbnails are close to each other. Clusters that only hold
+/// copies of the same file are already reported as exact duplicates.
+fn probable_duplicates(publications: &[Publication]) -> Vec<DuplicateGroup> {
+    let hashed: Vec<(&Publication, i64)> = publications
+        .iter()
+        .filter_map(|the_publication| {
+            the_publication
+                .thumbnail_hash
+                .map(|hash| (the_publication, hash))
+        })
+        .collect();
+
+    // Split the hashes into one more band than the bits they may differ in: hashes that are
+    // close enough have at least one band in common, so only the hashes sharing a band need to
+    // be compared.
+    let mut buckets: HashMap<(u32, i64), Vec<usize>> = HashMap::new();
+    for (index, &(_, hash)) in hashed.iter().enumerate() {
+        for band in 0..BANDS {
+            buckets
+                .entry((band, band_of(hash, band)))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    // union-find over the indexes of `hashed`
+    let mut parents: Vec<usize> = (0..hashed.len()).collect();
+    for bucket in buckets.values().filter(|bucket| bucket.len() > 1) {
+        for (i, &a) in bucket.iter().enumerate() {
+            for &b in bucket[(i + 1)..].iter() {
+                let (root_a, root_b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+                if root_a != root_b
+                    && hash_distance(hashed[a].1, hashed[b].1) <= MAX_THUMBNAIL_DISTANCE
+                {
+                    parents[root_b] = root_a;
+                }
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<&Publication>> = HashMap::new();
+    for index in 0..hashed.len() {
+        let cluster = find_root(&mut parents, index);
+        clusters.entry(cluster).or_default().push(hashed[index].0);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = clusters
+        .into_iter()
+        .map(|(_, cluster)| cluster)
+        .filter(|cluster| {
+            let first_hash = &cluster[0].file_hash;
+            cluster.len() > 1
+                && (first_hash.is_none()
+                    || cluster
+                        .iter()
+                        .any(|the_publication| &the_publication.file_hash != first_hash))
+        })
+        .map(|cluster| DuplicateGroup {
+            kind: DuplicateKind::Probable,
+            publications: cluster.into_iter().cloned().collect(),
+        })
+        .collect();
+    groups.sort_by_key(|group| group.publications[0].id);
+    groups
+}
+
+/// Bits `band` covers out of the 64 of a hash, the last band takes the remainder.
+fn band_of(hash: i64, band: u32) -> i64 {
+    let width = 64 / BANDS;
+    let shift = band * width;
+    let bits = if band == BANDS - 1 { 64 - shift } else { width };
+    ((hash as u64 >> shift) & (u64::max_value() >> (64 - bits))) as i64
+}
+
+fn find_root(parents: &mut Vec<usize>, index: usize) -> usize {
+    let mut index = index;
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Fold duplicates into one publication: their categories, tags, reading progress and recently
+/// read entries move over to the kept publication and the duplicates are deleted. The files of
+/// the duplicates stay on disk, their hashes are recorded so that scans don't import them again.
+#[derive(Debug)]
+pub struct Merge {
+    pub publication_id: PublicationId,
+    pub duplicate_ids: Vec<PublicationId>,
+}
+impl Message for Merge {
+    type Result = Result<Publication, Error>;
+}
+impl Handler<Merge> for DbExecutor {
+    type Result = Result<Publication, Error>;
+
+    fn handle(&mut self, msg: Merge, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let Merge {
+            publication_id: kept_id,
+            duplicate_ids,
+        } = msg;
+
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let kept = publication
+                    .find(kept_id)
+                    .first::<Publication>(&*connection)?;
+                for duplicate_id in duplicate_ids.into_iter().filter(|&dup| dup != kept_id) {
+                    let duplicate = publication
+                        .find(duplicate_id)
+                        .first::<Publication>(&*connection)?;
+                    move_categories(connection, duplicate_id, kept_id)?;
+                    move_tags(connection, duplicate_id, kept_id)?;
+                    move_progress(connection, duplicate_id, kept_id)?;
+                    move_recent(connection, duplicate_id, kept_id)?;
+                    if let Some(hash) = duplicate.file_hash {
+                        use schema::ignored_file::dsl::ignored_file;
+                        diesel::replace_into(ignored_file)
+                            .values(&IgnoredFile {
+                                file_hash: hash,
+                                file: duplicate.file,
+                                timestamp: None,
+                            })
+                            .execute(&*connection)?;
+                    }
+                    diesel::delete(publication.find(duplicate_id)).execute(&*connection)?;
+                }
+                Ok(kept)
+            })
+            .map_err(|err| match err {
+                diesel::result::Error::NotFound => {
+                    actix_web::error::ErrorNotFound("Publication not found")
+                }
+                err => actix_web::error::ErrorInternalServerError(err),
+            })
+    }
+}
+
+fn move_categories(
+    connection: &SqliteConnection,
+    from: PublicationId,
+    to: PublicationId,
+) -> QueryResult<()> {
+    use schema::publication_category::dsl;
+
+    let rows = dsl::publication_category
+        .filter(dsl::publication_id.eq(from))
+        .load::<PublicationCategory>(&*connection)?;
+    for row in rows.into_iter() {
+        diesel::replace_into(dsl::publication_category)
+            .values(&PublicationCategory {
+                publication_id: to,
+                category_id: row.category_id,
+            })
+            .execute(&*connection)?;
+    }
+    diesel::delete(dsl::publication_category.filter(dsl::publication_id.eq(from)))
+        .execute(&*connection)?;
+    Ok(())
+}
+
+fn move_tags(
+    connection: &SqliteConnection,
+    from: PublicationId,
+    to: PublicationId,
+) -> QueryResult<()> {
+    use schema::publication_tag::dsl;
+
+    let rows = dsl::publication_tag
+        .filter(dsl::publication_id.eq(from))
+        .load::<PublicationTag>(&*connection)?;
+    for row in rows.into_iter() {
+        diesel::replace_into(dsl::publication_tag)
+            .values(&PublicationTag {
+                publication_id: to,
+                tag_id: row.tag_id,
+            })
+            .execute(&*connection)?;
+    }
+    diesel::delete(dsl::publication_tag.filter(dsl::publication_id.eq(from)))
+        .execute(&*connection)?;
+    Ok(())
+}
+
+/// Readers keep whichever of the two they got furthest in.
+fn move_progress(
+    connection: &SqliteConnection,
+    from: PublicationId,
+    to: PublicationId,
+) -> QueryResult<()> {
+    use schema::publication_progress::dsl;
+
+    let rows = dsl::publication_progress
+        .filter(dsl::publication_id.eq(from))
+        .load::<PublicationProgress>(&*connection)?;
+    for row in rows.into_iter() {
+        let existing = dsl::publication_progress
+            .find((row.user_id, to))
+            .first::<PublicationProgress>(&*connection)
+            .optional()?;
+        let the_progress = match existing {
+            Some(existing) if existing.progress >= row.progress => continue,
+            _ => row.progress,
+        };
+        diesel::replace_into(dsl::publication_progress)
+            .values(&PublicationProgress {
+                user_id: row.user_id,
+                publication_id: to,
+                progress: the_progress,
+            })
+            .execute(&*connection)?;
+    }
+    diesel::delete(dsl::publication_progress.filter(dsl::publication_id.eq(from)))
+        .execute(&*connection)?;
+    Ok(())
+}
+
+/// Readers keep whichever of the two they read last.
+fn move_recent(
+    connection: &SqliteConnection,
+    from: PublicationId,
+    to: PublicationId,
+) -> QueryResult<()> {
+    use schema::recent_publication::dsl;
+
+    let rows = dsl::recent_publication
+        .filter(dsl::publication_id.eq(from))
+        .load::<RecentPublication>(&*connection)?;
+    for row in rows.into_iter() {
+        let existing = dsl::recent_publication
+            .find((row.user_id, to))
+            .first::<RecentPublication>(&*connection)
+            .optional()?;
+        if let Some(existing) = existing {
+            if existing.timestamp >= row.timestamp {
+                continue;
+            }
+        }
+        diesel::replace_into(dsl::recent_publication)
+            .values(&RecentPublication {
+                user_id: row.user_id,
+                publication_id: to,
+                timestamp: row.timestamp,
+            })
+            .execute(&*connection)?;
+    }
+    diesel::delete(dsl::recent_publication.filter(dsl::publication_id.eq(from)))
+        .execute(&*connection)?;
+    Ok(())
+}
+
+/// Hashes of the files that were merged away, for the scanner to skip.
+#[derive(Debug)]
+pub struct ListIgnored {}
+impl Message for ListIgnored {
+    type Result = Result<Vec<IgnoredFile>, Error>;
+}
+impl Handler<ListIgnored> for DbExecutor {
+    type Result = Result<Vec<IgnoredFile>, Error>;
+
+    fn handle(&mut self, _msg: ListIgnored, _: &mut Self::Context) -> Self::Result {
+        use schema::ignored_file::dsl::ignored_file;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        ignored_file
+            .load::<IgnoredFile>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
@@ -0,0 +1,100 @@
+extern crate diesel;
+
+use actix::prelude::*;
+use actix_web::Error;
+use db::executor::DbExecutor;
+use diesel::prelude::*;
+use models::{CategoryId, Library, LibraryId, MediaTypeId, NewLibrary};
+use schema::library::dsl::*;
+
+pub struct List {}
+impl Message for List {
+    type Result = Result<Vec<Library>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<Library>, Error>;
+
+    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        library
+            .order_by(id.asc())
+            .load::<Library>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// A library as sent by the client. Libraries without an id are new.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LibraryData {
+    pub id: Option<LibraryId>,
+    pub name: String,
+    pub path: String,
+    #[serde(default = "default_media_type_id")]
+    pub media_type_id: MediaTypeId,
+    pub category_id: Option<CategoryId>,
+}
+
+fn default_media_type_id() -> MediaTypeId {
+    1
+}
+
+/// Replace the libraries with the given list: existing ones are updated, new ones are created and
+/// the ones that aren't in the list anymore are removed. Publications imported from a removed
+/// library stay in the database and will be marked as missing by the next scan. Ids that don't
+/// belong to an existing library are refused.
+pub struct Replace {
+    pub libraries: Vec<LibraryData>,
+}
+impl Message for Replace {
+    type Result = Result<Vec<Library>, Error>;
+}
+impl Handler<Replace> for DbExecutor {
+    type Result = Result<Vec<Library>, Error>;
+
+    fn handle(&mut self, msg: Replace, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let kept_ids: Vec<LibraryId> = msg.libraries.iter().filter_map(|data| data.id).collect();
+        let known_ids: Vec<LibraryId> = library
+            .select(id)
+            .filter(id.eq_any(&kept_ids))
+            .load(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        if let Some(unknown_id) = kept_ids.iter().find(|kept| !known_ids.contains(kept)) {
+            return Err(actix_web::error::ErrorBadRequest(format!(
+                "There is no library {}",
+                unknown_id
+            )));
+        }
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                diesel::delete(library.filter(id.ne_all(&kept_ids))).execute(&*connection)?;
+                for data in msg.libraries.into_iter() {
+                    match data.id {
+                        Some(library_id) => {
+                            diesel::update(library.filter(id.eq(library_id)))
+                                .set(&Library {
+                                    id: library_id,
+                                    name: data.name,
+                                    path: data.path,
+                                    media_type_id: data.media_type_id,
+                                    category_id: data.category_id,
+                                })
+                                .execute(&*connection)?;
+                        }
+                        None => {
+                            diesel::insert_into(library)
+                                .values(&NewLibrary {
+                                    name: data.name,
+                                    path: data.path,
+                                    media_type_id: data.media_type_id,
+                                    category_id: data.category_id,
+                                })
+                                .execute(&*connection)?;
+                        }
+                    }
+                }
+                library.order_by(id.asc()).load::<Library>(&*connection)
+            })
+            .map_err(actix_web::error::ErrorBadRequest)
+    }
+}
@@ -0,0 +1,52 @@
+extern crate diesel;
+
+use actix::prelude::*;
+use actix_web::Error;
+use db::executor::DbExecutor;
+use diesel::prelude::*;
+use models::{MediaTypeHint, NewMediaTypeHint};
+use schema::media_type_hint::dsl::*;
+
+pub struct List {}
+impl Message for List {
+    type Result = Result<Vec<MediaTypeHint>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<MediaTypeHint>, Error>;
+
+    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        media_type_hint
+            .order_by(folder.asc())
+            .load::<MediaTypeHint>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Replace all the hints with the given list.
+pub struct Replace {
+    pub hints: Vec<NewMediaTypeHint>,
+}
+impl Message for Replace {
+    type Result = Result<Vec<MediaTypeHint>, Error>;
+}
+impl Handler<Replace> for DbExecutor {
+    type Result = Result<Vec<MediaTypeHint>, Error>;
+
+    fn handle(&mut self, msg: Replace, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                diesel::delete(media_type_hint).execute(&*connection)?;
+                for hint in msg.hints.iter() {
+                    diesel::insert_into(media_type_hint)
+                        .values(hint)
+                        .execute(&*connection)?;
+                }
+                media_type_hint
+                    .order_by(folder.asc())
+                    .load::<MediaTypeHint>(&*connection)
+            })
+            .map_err(actix_web::error::ErrorBadRequest)
+    }
+}
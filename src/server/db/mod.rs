@@ -4,12 +4,19 @@ use r2d2_diesel::ConnectionManager;
 
 pub mod author;
 pub mod category;
+pub mod category_rule;
+pub mod duplicate;
 pub mod executor;
+pub mod library;
 pub mod media_type;
+pub mod media_type_hint;
 pub mod publication;
 pub mod publication_category;
+pub mod publication_tag;
+pub mod search;
 pub mod setting;
 pub mod tag;
+pub mod user;
 
 pub fn create_db_pool(database_url: &str) -> Pool<ConnectionManager<SqliteConnection>> {
     let manager = ConnectionManager::<SqliteConnection>::new(database_url);
@@ -1,31 +1,90 @@
 extern crate diesel;
 
 use actix_web::Error;
+use diesel::dsl::sql;
 use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable, Text, Timestamp};
+use diesel::sqlite::Sqlite;
 
 use actix::prelude::*;
 use db::executor::DbExecutor;
+use fs::thumbnail as thumbnail_file;
 use models::{
-    Category, NewPublication, Publication, PublicationCategory, PublicationProgress,
-    RecentPublication,
+    Category, NewPublication, Paginated, Publication, PublicationCategory, PublicationProgress,
+    RecentPublication, UserId,
 };
 use schema::publication::dsl::*;
+use schema::publication::BoxedQuery;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sort {
+    Title,
+    Timestamp,
+    Author,
+    LastRead,
+}
+
+impl Default for Sort {
+    fn default() -> Self {
+        Sort::Title
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Filter {
+    pub media_format: Option<String>,
+    pub media_type_id: Option<i32>,
+    pub author_id: Option<i32>,
+    pub tag_id: Option<i32>,
+    /// Publications whose file is gone are left out unless asked for.
+    pub missing: Option<bool>,
+}
 
 #[derive(Debug)]
-pub struct List {}
+pub struct List {
+    pub user_id: UserId,
+    pub page: i64,
+    pub per_page: i64,
+    pub sort: Sort,
+    pub filter: Filter,
+}
 impl Message for List {
-    type Result = Result<Vec<Publication>, Error>;
+    type Result = Result<Paginated<Publication>, Error>;
 }
 impl Handler<List> for DbExecutor {
+    type Result = Result<Paginated<Publication>, Error>;
+
+    fn handle(&mut self, msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        list_publications(
+            connection,
+            msg.user_id,
+            None,
+            &msg.filter,
+            msg.sort,
+            msg.page,
+            msg.per_page,
+        )
+        .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Every publication, including the missing ones, for the scanner to reconcile against.
+#[derive(Debug)]
+pub struct ListAll {}
+impl Message for ListAll {
+    type Result = Result<Vec<Publication>, Error>;
+}
+impl Handler<ListAll> for DbExecutor {
     type Result = Result<Vec<Publication>, Error>;
 
-    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, _msg: ListAll, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
-        let publications = publication
-            .order_by(title.asc())
+        publication
             .load::<Publication>(&*connection)
-            .expect("Error loading publications");
-        Ok(publications)
+            .map_err(actix_web::error::ErrorInternalServerError)
     }
 }
 
@@ -53,37 +112,54 @@ impl Handler<Create> for DbExecutor {
     }
 }
 
+/// Outcome of `CreateBatch`.
+#[derive(Debug)]
+pub struct CreatedBatch {
+    pub publications: Vec<Publication>,
+    /// Files the database refused, with the reason.
+    pub refused: HashMap<String, String>,
+}
+
 #[derive(Debug)]
 pub struct CreateBatch(pub Vec<NewPublication>);
 impl Message for CreateBatch {
-    type Result = Result<Vec<Publication>, Error>;
+    type Result = Result<CreatedBatch, Error>;
 }
 impl Handler<CreateBatch> for DbExecutor {
-    type Result = Result<Vec<Publication>, Error>;
+    type Result = Result<CreatedBatch, Error>;
 
     fn handle(&mut self, msg: CreateBatch, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
         let new_publications = msg.0;
         let mut filenames: Vec<String> = Vec::new();
+        let mut refused = HashMap::new();
 
         for new_publication in new_publications.iter() {
             let existing_publication =
                 get_publication_by_file(connection, &new_publication.file).ok();
             if existing_publication.is_none() {
-                println!("Adding {} into database", new_publication.title);
-                diesel::insert_into(publication)
+                // one file the database refuses shouldn't keep the rest out
+                let inserted = diesel::insert_into(publication)
                     .values(new_publication)
-                    .execute(&*connection)
-                    .expect("Error inserting publication");
-                filenames.push(new_publication.file.to_string());
+                    .execute(&*connection);
+                match inserted {
+                    Ok(_) => filenames.push(new_publication.file.to_string()),
+                    Err(err) => {
+                        eprintln!("Unable to add {}: {}", new_publication.file, err);
+                        refused.insert(new_publication.file.to_string(), err.to_string());
+                    }
+                }
             }
         }
 
-        let publications = publication
+        publication
             .filter(file.eq_any(filenames))
             .load::<Publication>(&*connection)
-            .expect("Unable to query new publications");
-        Ok(publications)
+            .map(|publications| CreatedBatch {
+                publications,
+                refused,
+            })
+            .map_err(actix_web::error::ErrorInternalServerError)
     }
 }
 
@@ -102,7 +178,27 @@ impl Handler<Update> for DbExecutor {
         diesel::update(publication.filter(id.eq(msg.publication.id)))
             .set(msg.publication)
             .execute(&*connection)
-            .expect("Error updating publication");
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MarkMissing {
+    pub publication_ids: Vec<i32>,
+}
+impl Message for MarkMissing {
+    type Result = Result<(), Error>;
+}
+impl Handler<MarkMissing> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: MarkMissing, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::update(publication.filter(id.eq_any(msg.publication_ids)))
+            .set(missing.eq(true))
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
         Ok(())
     }
 }
@@ -120,8 +216,12 @@ impl Handler<UpdateThumbnail> for DbExecutor {
 
     fn handle(&mut self, msg: UpdateThumbnail, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
+        let the_thumbnail_hash = thumbnail_file::perceptual_hash(&msg.thumbnail).ok();
         diesel::update(publication.filter(id.eq(msg.publication_id)))
-            .set(thumbnail.eq(Some(msg.thumbnail)))
+            .set((
+                thumbnail.eq(Some(msg.thumbnail)),
+                thumbnail_hash.eq(the_thumbnail_hash),
+            ))
             .execute(&*connection)
             .expect("Error updating thumbnail");
         Ok(())
@@ -147,7 +247,10 @@ impl Handler<DeleteThumbnail> for DbExecutor {
         match the_thumbnail {
             Some(_) => {
                 diesel::update(publication.filter(id.eq(msg.publication_id)))
-                    .set(thumbnail.eq::<Option<&str>>(None))
+                    .set((
+                        thumbnail.eq::<Option<&str>>(None),
+                        thumbnail_hash.eq::<Option<i64>>(None),
+                    ))
                     .execute(&*connection)
                     .map_err(actix_web::error::ErrorInternalServerError)?;
                 Ok(the_thumbnail)
@@ -198,7 +301,10 @@ impl Handler<Get> for DbExecutor {
 }
 
 #[derive(Debug)]
-pub struct AddRecent(pub i32);
+pub struct AddRecent {
+    pub user_id: UserId,
+    pub publication_id: i32,
+}
 impl Message for AddRecent {
     type Result = Result<(), Error>;
 }
@@ -208,13 +314,21 @@ impl Handler<AddRecent> for DbExecutor {
         use schema::recent_publication::dsl;
         let connection: &SqliteConnection = &self.0.get().unwrap();
 
-        diesel::delete(dsl::recent_publication.filter(dsl::publication_id.eq(msg.0)))
-            .execute(&*connection)
-            .expect(&format!("Error deleting recent_publication {}", msg.0));
+        diesel::delete(
+            dsl::recent_publication
+                .filter(dsl::user_id.eq(msg.user_id))
+                .filter(dsl::publication_id.eq(msg.publication_id)),
+        )
+        .execute(&*connection)
+        .expect(&format!(
+            "Error deleting recent_publication {}",
+            msg.publication_id
+        ));
 
         diesel::insert_into(dsl::recent_publication)
             .values(RecentPublication {
-                publication_id: msg.0,
+                user_id: msg.user_id,
+                publication_id: msg.publication_id,
                 timestamp: None,
             })
             .execute(&*connection)
@@ -263,6 +377,7 @@ impl Handler<ListRecentlyAdded> for DbExecutor {
 
 #[derive(Debug)]
 pub struct ListRecentlyRead {
+    pub user_id: UserId,
     pub count: i64,
 }
 impl Message for ListRecentlyRead {
@@ -276,6 +391,7 @@ impl Handler<ListRecentlyRead> for DbExecutor {
 
         let row: Vec<(Publication, RecentPublication)> = publication
             .inner_join(dsl::recent_publication)
+            .filter(dsl::user_id.eq(msg.user_id))
             .order_by(dsl::timestamp.desc())
             .limit(msg.count)
             .load(&*connection)
@@ -289,39 +405,125 @@ impl Handler<ListRecentlyRead> for DbExecutor {
 
 #[derive(Debug)]
 pub struct ListByCategory {
+    pub user_id: UserId,
     pub category_id: i32,
+    pub page: i64,
+    pub per_page: i64,
+    pub sort: Sort,
+    pub filter: Filter,
 }
 impl Message for ListByCategory {
-    type Result = Result<Vec<Publication>, Error>;
+    type Result = Result<Paginated<Publication>, Error>;
 }
 impl Handler<ListByCategory> for DbExecutor {
-    type Result = Result<Vec<Publication>, Error>;
+    type Result = Result<Paginated<Publication>, Error>;
 
     fn handle(&mut self, msg: ListByCategory, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
-        use schema::publication::dsl as publication;
-        use schema::publication_category::dsl as publication_category;
-
         let categories: Vec<i32> = get_category_and_descendants(msg.category_id, &connection)
-            .expect("Invalid category id")
+            .map_err(actix_web::error::ErrorNotFound)?
             .iter()
             .map(|category| category.id)
             .collect();
 
-        let the_publication_id = publication_category::publication_category
-            .filter(publication_category::category_id.eq_any(categories))
-            .select(publication_category::publication_id)
-            .load::<i32>(&*connection)
-            .expect("Error getting publications id");
-
-        let publications = publication::publication
-            .filter(publication::id.eq_any(the_publication_id))
-            .order_by(publication::title.asc())
-            .load::<Publication>(&*connection)
-            .expect("Error getting publications");
+        list_publications(
+            connection,
+            msg.user_id,
+            Some(categories),
+            &msg.filter,
+            msg.sort,
+            msg.page,
+            msg.per_page,
+        )
+        .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
 
-        Ok(publications)
+fn filter_publications<'a>(
+    categories: Option<Vec<i32>>,
+    filter: &'a Filter,
+) -> BoxedQuery<'a, Sqlite> {
+    use schema::publication_category::dsl as publication_category;
+    use schema::publication_tag::dsl as publication_tag;
+
+    let mut query = publication
+        .filter(missing.eq(filter.missing.unwrap_or(false)))
+        .into_boxed();
+    if let Some(categories) = categories {
+        query = query.filter(
+            id.eq_any(
+                publication_category::publication_category
+                    .filter(publication_category::category_id.eq_any(categories))
+                    .select(publication_category::publication_id),
+            ),
+        );
+    }
+    if let Some(ref the_media_format) = filter.media_format {
+        query = query.filter(media_format.eq(the_media_format));
+    }
+    if let Some(the_media_type_id) = filter.media_type_id {
+        query = query.filter(media_type_id.eq(the_media_type_id));
+    }
+    if let Some(the_author_id) = filter.author_id {
+        query = query.filter(author_id.eq(the_author_id));
     }
+    if let Some(the_tag_id) = filter.tag_id {
+        query = query.filter(
+            id.eq_any(
+                publication_tag::publication_tag
+                    .filter(publication_tag::tag_id.eq(the_tag_id))
+                    .select(publication_tag::publication_id),
+            ),
+        );
+    }
+    query
+}
+
+fn list_publications(
+    connection: &SqliteConnection,
+    user_id: UserId,
+    categories: Option<Vec<i32>>,
+    filter: &Filter,
+    sort: Sort,
+    page: i64,
+    per_page: i64,
+) -> QueryResult<Paginated<Publication>> {
+    let total = filter_publications(categories.clone(), filter)
+        .count()
+        .get_result::<i64>(&*connection)?;
+
+    let query = filter_publications(categories, filter);
+    let query = match sort {
+        Sort::Title => query.order_by(title.asc()),
+        Sort::Timestamp => query.order_by(timestamp.desc()),
+        Sort::Author => query.order_by(
+            sql::<Text>("(SELECT author.name FROM author WHERE author.id = publication.author_id)")
+                .asc(),
+        ),
+        Sort::LastRead => query.order_by(
+            sql::<Nullable<Timestamp>>(
+                "(SELECT recent_publication.timestamp FROM recent_publication \
+                 WHERE recent_publication.publication_id = publication.id \
+                 AND recent_publication.user_id = ",
+            )
+            .bind::<Integer, _>(user_id)
+            .sql(")")
+            .desc(),
+        ),
+    };
+
+    let publications = query
+        .then_order_by(title.asc())
+        .limit(per_page)
+        .offset((page - 1) * per_page)
+        .load::<Publication>(&*connection)?;
+
+    Ok(Paginated {
+        items: publications,
+        total,
+        page,
+        per_page,
+    })
 }
 
 fn get_category(category_id: i32, connection: &SqliteConnection) -> QueryResult<Category> {
@@ -414,6 +616,7 @@ fn get_publication(
 
 #[derive(Debug)]
 pub struct UpdateProgress {
+    pub user_id: UserId,
     pub publication_id: i32,
     pub progress: f32,
 }
@@ -425,16 +628,32 @@ impl Handler<UpdateProgress> for DbExecutor {
 
     fn handle(&mut self, msg: UpdateProgress, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
-        let option = get_publication_progress(connection, msg.publication_id)?;
+        let UpdateProgress {
+            user_id: the_user_id,
+            publication_id: the_publication_id,
+            progress: the_progress,
+        } = msg;
+        let option = get_publication_progress(connection, the_user_id, the_publication_id)?;
         match option {
-            Some(_) => update_publication_progress(connection, msg.publication_id, msg.progress),
-            None => insert_publication_progress(connection, msg.publication_id, msg.progress),
+            Some(_) => update_publication_progress(
+                connection,
+                the_user_id,
+                the_publication_id,
+                the_progress,
+            ),
+            None => insert_publication_progress(
+                connection,
+                the_user_id,
+                the_publication_id,
+                the_progress,
+            ),
         }
     }
 }
 
 #[derive(Debug)]
 pub struct GetProgress {
+    pub user_id: UserId,
     pub publication_id: i32,
 }
 impl Message for GetProgress {
@@ -445,7 +664,7 @@ impl Handler<GetProgress> for DbExecutor {
 
     fn handle(&mut self, msg: GetProgress, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
-        get_publication_progress(connection, msg.publication_id).map(|option| {
+        get_publication_progress(connection, msg.user_id, msg.publication_id).map(|option| {
             option
                 .map(|publication_progress| publication_progress.progress)
                 .unwrap_or(0f32)
@@ -455,11 +674,13 @@ impl Handler<GetProgress> for DbExecutor {
 
 fn get_publication_progress(
     connection: &SqliteConnection,
+    user_id: UserId,
     publication_id: i32,
 ) -> Result<Option<PublicationProgress>, Error> {
     use schema::publication_progress::dsl;
 
     let mut row: Vec<PublicationProgress> = dsl::publication_progress
+        .filter(dsl::user_id.eq(user_id))
         .filter(dsl::publication_id.eq(publication_id))
         .limit(1)
         .load(&*connection)
@@ -470,12 +691,14 @@ fn get_publication_progress(
 
 fn insert_publication_progress(
     connection: &SqliteConnection,
+    the_user_id: UserId,
     the_publication_id: i32,
     the_progress: f32,
 ) -> Result<(), Error> {
     use schema::publication_progress::dsl::*;
     diesel::insert_into(publication_progress)
         .values(PublicationProgress {
+            user_id: the_user_id,
             publication_id: the_publication_id,
             progress: the_progress,
         })
@@ -486,13 +709,18 @@ fn insert_publication_progress(
 }
 fn update_publication_progress(
     connection: &SqliteConnection,
+    the_user_id: UserId,
     the_publication_id: i32,
     the_progress: f32,
 ) -> Result<(), Error> {
     use schema::publication_progress::dsl::*;
-    diesel::update(publication_progress.filter(publication_id.eq(the_publication_id)))
-        .set(progress.eq(the_progress))
-        .execute(&*connection)
-        .expect("Error updating publication_progress");
+    diesel::update(
+        publication_progress
+            .filter(user_id.eq(the_user_id))
+            .filter(publication_id.eq(the_publication_id)),
+    )
+    .set(progress.eq(the_progress))
+    .execute(&*connection)
+    .expect("Error updating publication_progress");
     Ok(())
 }
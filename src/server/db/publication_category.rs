@@ -60,7 +60,7 @@ impl Handler<CreateBatch> for DbExecutor {
         diesel::insert_into(publication_category)
             .values(new_publication_categories)
             .execute(&*connection)
-            .expect("Error inserting publication categories");
+            .map_err(actix_web::error::ErrorInternalServerError)?;
         Ok(())
     }
 }
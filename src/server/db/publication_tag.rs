@@ -0,0 +1,111 @@
+extern crate diesel;
+
+use actix_web::Error;
+use diesel::prelude::*;
+
+use actix::prelude::*;
+use db::executor::DbExecutor;
+use models::{PublicationId, PublicationTag, Tag, TagId};
+use schema::publication_tag::dsl::*;
+
+pub struct List {
+    pub publication_id: PublicationId,
+}
+impl Message for List {
+    type Result = Result<Vec<Tag>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<Tag>, Error>;
+
+    fn handle(&mut self, msg: List, _: &mut Self::Context) -> Self::Result {
+        use schema::tag::dsl as tag;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        tag::tag
+            .filter(
+                tag::id.eq_any(
+                    publication_tag
+                        .filter(publication_id.eq(msg.publication_id))
+                        .select(tag_id),
+                ),
+            )
+            .order_by(tag::name.asc())
+            .load::<Tag>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+#[derive(Debug)]
+pub struct Create(pub PublicationTag);
+impl Message for Create {
+    type Result = Result<(), Error>;
+}
+impl Handler<Create> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Create, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::replace_into(publication_tag)
+            .values(msg.0)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
+
+/// Tag every publication in `publication_ids` with every tag in `tag_ids`.
+#[derive(Debug, Deserialize)]
+pub struct CreateBatch {
+    pub publication_ids: Vec<PublicationId>,
+    pub tag_ids: Vec<TagId>,
+}
+impl Message for CreateBatch {
+    type Result = Result<(), Error>;
+}
+impl Handler<CreateBatch> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: CreateBatch, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let mut publication_tags: Vec<PublicationTag> = Vec::new();
+        for the_publication_id in msg.publication_ids.iter() {
+            for the_tag_id in msg.tag_ids.iter() {
+                publication_tags.push(PublicationTag {
+                    publication_id: *the_publication_id,
+                    tag_id: *the_tag_id,
+                });
+            }
+        }
+
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                for new_publication_tag in publication_tags.iter() {
+                    diesel::replace_into(publication_tag)
+                        .values(new_publication_tag)
+                        .execute(&*connection)?;
+                }
+                Ok(())
+            })
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+#[derive(Debug)]
+pub struct Delete(pub PublicationTag);
+impl Message for Delete {
+    type Result = Result<(), Error>;
+}
+impl Handler<Delete> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::delete(
+            publication_tag
+                .filter(publication_id.eq(msg.0.publication_id))
+                .filter(tag_id.eq(msg.0.tag_id)),
+        )
+        .execute(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
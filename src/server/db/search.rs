@@ -0,0 +1,272 @@
+extern crate diesel;
+
+use actix::prelude::*;
+use actix_web::Error;
+use chrono::NaiveDateTime;
+use db::executor::DbExecutor;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text, Timestamp};
+use models::{ContentMatch, Paginated, Publication, PublicationId, EPUB};
+
+/// Delimit the matches in a content snippet. Control characters don't show up in book text, so
+/// the snippet can be HTML escaped before they are turned into `<mark>` tags.
+const MATCH_START: &str = "\u{2}";
+const MATCH_END: &str = "\u{3}";
+
+#[derive(Debug, QueryableByName)]
+struct Count {
+    #[sql_type = "BigInt"]
+    count: i64,
+}
+
+#[derive(Debug)]
+pub struct Search {
+    pub query: String,
+    pub page: i64,
+    pub per_page: i64,
+}
+impl Message for Search {
+    type Result = Result<Paginated<Publication>, Error>;
+}
+impl Handler<Search> for DbExecutor {
+    type Result = Result<Paginated<Publication>, Error>;
+
+    fn handle(&mut self, msg: Search, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let match_query = to_match_query(&msg.query);
+
+        let total = diesel::sql_query(
+            "SELECT COUNT(*) AS count FROM publication_search WHERE publication_search MATCH ?",
+        )
+        .bind::<Text, _>(&match_query)
+        .get_result::<Count>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .count;
+
+        // Matches on the title weigh the most, followed by author, tags and categories.
+        let publications = diesel::sql_query(
+            "SELECT publication.* FROM publication_search \
+             INNER JOIN publication ON publication.id = publication_search.publication_id \
+             WHERE publication_search MATCH ? \
+             ORDER BY bm25(publication_search, 0.0, 10.0, 5.0, 2.0, 1.0) \
+             LIMIT ? OFFSET ?",
+        )
+        .bind::<Text, _>(&match_query)
+        .bind::<BigInt, _>(msg.per_page)
+        .bind::<BigInt, _>((msg.page - 1) * msg.per_page)
+        .load::<Publication>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        Ok(Paginated {
+            items: publications,
+            total,
+            page: msg.page,
+            per_page: msg.per_page,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexContent {
+    pub publication_id: PublicationId,
+    pub pages: Vec<String>,
+}
+impl Message for IndexContent {
+    type Result = Result<(), Error>;
+}
+impl Handler<IndexContent> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: IndexContent, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                diesel::sql_query(
+                    "DELETE FROM publication_content_search WHERE publication_id = ?",
+                )
+                .bind::<Integer, _>(msg.publication_id)
+                .execute(&*connection)?;
+                diesel::sql_query("DELETE FROM content_index_failure WHERE publication_id = ?")
+                    .bind::<Integer, _>(msg.publication_id)
+                    .execute(&*connection)?;
+                for (page, content) in msg.pages.iter().enumerate() {
+                    diesel::sql_query(
+                        "INSERT INTO publication_content_search (publication_id, page, content) \
+                         VALUES (?, ?, ?)",
+                    )
+                    .bind::<Integer, _>(msg.publication_id)
+                    .bind::<Integer, _>(page as i32)
+                    .bind::<Text, _>(content)
+                    .execute(&*connection)?;
+                }
+                Ok(())
+            })
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Forget the indexed content of publications whose file changed, so the next scan indexes them
+/// again.
+#[derive(Debug)]
+pub struct ClearContent {
+    pub publication_ids: Vec<PublicationId>,
+}
+impl Message for ClearContent {
+    type Result = Result<(), Error>;
+}
+impl Handler<ClearContent> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: ClearContent, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                for publication_id in msg.publication_ids.iter() {
+                    diesel::sql_query(
+                        "DELETE FROM publication_content_search WHERE publication_id = ?",
+                    )
+                    .bind::<Integer, _>(publication_id)
+                    .execute(&*connection)?;
+                    diesel::sql_query("DELETE FROM content_index_failure WHERE publication_id = ?")
+                        .bind::<Integer, _>(publication_id)
+                        .execute(&*connection)?;
+                }
+                Ok(())
+            })
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Remember that the content of a publication couldn't be extracted or had no text, so it isn't
+/// tried again until the file changes.
+#[derive(Debug)]
+pub struct RecordIndexFailure {
+    pub publication_id: PublicationId,
+    pub file_size: Option<i64>,
+    pub file_modified: Option<NaiveDateTime>,
+}
+impl Message for RecordIndexFailure {
+    type Result = Result<(), Error>;
+}
+impl Handler<RecordIndexFailure> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: RecordIndexFailure, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::sql_query(
+            "INSERT OR REPLACE INTO content_index_failure (publication_id, file_size, file_modified) \
+             VALUES (?, ?, ?)",
+        )
+        .bind::<Integer, _>(msg.publication_id)
+        .bind::<Nullable<BigInt>, _>(msg.file_size)
+        .bind::<Nullable<Timestamp>, _>(msg.file_modified)
+        .execute(&*connection)
+        .map(|_| ())
+        .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// EPUB publications that don't have any of their content in the search index yet, leaving out
+/// the ones that already failed to index and haven't changed since.
+#[derive(Debug)]
+pub struct ListUnindexed {}
+impl Message for ListUnindexed {
+    type Result = Result<Vec<Publication>, Error>;
+}
+impl Handler<ListUnindexed> for DbExecutor {
+    type Result = Result<Vec<Publication>, Error>;
+
+    fn handle(&mut self, _msg: ListUnindexed, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::sql_query(
+            "SELECT * FROM publication WHERE media_format = ? \
+             AND id NOT IN (SELECT publication_id FROM publication_content_search) \
+             AND NOT EXISTS (SELECT 1 FROM content_index_failure \
+               WHERE content_index_failure.publication_id = publication.id \
+               AND content_index_failure.file_size IS publication.file_size \
+               AND content_index_failure.file_modified IS publication.file_modified)",
+        )
+        .bind::<Text, _>(EPUB)
+        .load::<Publication>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchContent {
+    pub query: String,
+    pub page: i64,
+    pub per_page: i64,
+}
+impl Message for SearchContent {
+    type Result = Result<Paginated<ContentMatch>, Error>;
+}
+impl Handler<SearchContent> for DbExecutor {
+    type Result = Result<Paginated<ContentMatch>, Error>;
+
+    fn handle(&mut self, msg: SearchContent, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let match_query = to_match_query(&msg.query);
+
+        let total = diesel::sql_query(
+            "SELECT COUNT(*) AS count FROM publication_content_search \
+             WHERE publication_content_search MATCH ?",
+        )
+        .bind::<Text, _>(&match_query)
+        .get_result::<Count>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .count;
+
+        let matches = diesel::sql_query(
+            "SELECT publication.id AS publication_id, publication.title AS title, \
+             publication_content_search.page AS page, \
+             snippet(publication_content_search, 2, ?, ?, '…', 24) AS snippet \
+             FROM publication_content_search \
+             INNER JOIN publication ON publication.id = publication_content_search.publication_id \
+             WHERE publication_content_search MATCH ? \
+             ORDER BY rank \
+             LIMIT ? OFFSET ?",
+        )
+        .bind::<Text, _>(MATCH_START)
+        .bind::<Text, _>(MATCH_END)
+        .bind::<Text, _>(&match_query)
+        .bind::<BigInt, _>(msg.per_page)
+        .bind::<BigInt, _>((msg.page - 1) * msg.per_page)
+        .load::<ContentMatch>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .into_iter()
+        .map(|content_match| ContentMatch {
+            snippet: highlight(&content_match.snippet),
+            ..content_match
+        })
+        .collect();
+
+        Ok(Paginated {
+            items: matches,
+            total,
+            page: msg.page,
+            per_page: msg.per_page,
+        })
+    }
+}
+
+/// Turn free text into an FTS5 query where every word is a quoted prefix term, so user input
+/// can't be interpreted as FTS5 syntax and partial words still match.
+pub fn to_match_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace("\"", "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// HTML escape the text of a snippet, the content is plain text extracted from the book so the
+/// only markup in the result are the `<mark>` tags around the matches.
+fn highlight(snippet: &str) -> String {
+    snippet
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
@@ -17,14 +17,41 @@ impl Handler<Get> for DbExecutor {
 
     fn handle(&mut self, _msg: Get, _: &mut Self::Context) -> Self::Result {
         let connection: &SqliteConnection = &self.0.get().unwrap();
-        let mut row = setting
-            .limit(1)
-            .load::<Setting>(&*connection)
-            .expect("Error loading setting");
-
-        match row.is_empty() {
-            true => panic!("setting can't be found"),
-            false => Ok(row.remove(0)),
-        }
+        let row = setting
+            .first::<Setting>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        Ok(row.unwrap_or_default())
+    }
+}
+
+/// Change the given settings, leaving the others as they are.
+pub struct Update {
+    pub min_category_score: Option<f64>,
+}
+impl Message for Update {
+    type Result = Result<Setting, Error>;
+}
+
+impl Handler<Update> for DbExecutor {
+    type Result = Result<Setting, Error>;
+
+    fn handle(&mut self, msg: Update, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let current = setting
+            .first::<Setting>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)?
+            .unwrap_or_default();
+        let updated = Setting {
+            min_category_score: msg.min_category_score.unwrap_or(current.min_category_score),
+            ..current
+        };
+        diesel::replace_into(setting)
+            .values(&updated)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(updated)
     }
 }
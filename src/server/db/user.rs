@@ -0,0 +1,261 @@
+extern crate diesel;
+
+use actix_web::Error;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use rand::distributions::Alphanumeric;
+use rand::{self, Rng};
+
+use actix::prelude::*;
+use db::executor::DbExecutor;
+use models::{NewUser, Session, User, UserId};
+use schema::user::dsl::*;
+
+const SESSION_DAYS: i64 = 30;
+const TOKEN_LENGTH: usize = 48;
+
+pub struct List {}
+impl Message for List {
+    type Result = Result<Vec<User>, Error>;
+}
+impl Handler<List> for DbExecutor {
+    type Result = Result<Vec<User>, Error>;
+
+    fn handle(&mut self, _msg: List, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        user.order_by(username.asc())
+            .load::<User>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Add a user, the password is hashed beforehand by `password::PasswordHasher`.
+#[derive(Debug)]
+pub struct Create {
+    pub username: String,
+    pub password_hash: String,
+    pub is_admin: bool,
+}
+impl Message for Create {
+    type Result = Result<User, Error>;
+}
+impl Handler<Create> for DbExecutor {
+    type Result = Result<User, Error>;
+
+    fn handle(&mut self, msg: Create, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        create_user(connection, msg.username, msg.password_hash, msg.is_admin)
+    }
+}
+
+/// Make sure there is an administrator who can log in. A fresh install gets a new one, while an
+/// administrator without a password (the default user created by the migrations, who owns the
+/// reading state from before there were users) is taken over: it is renamed to the given username
+/// and gets the given password.
+pub struct CreateAdmin {
+    pub username: String,
+    pub password_hash: String,
+}
+impl Message for CreateAdmin {
+    type Result = Result<Option<User>, Error>;
+}
+impl Handler<CreateAdmin> for DbExecutor {
+    type Result = Result<Option<User>, Error>;
+
+    fn handle(&mut self, msg: CreateAdmin, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let admin_count = user
+            .filter(is_admin.eq(true))
+            .filter(password_hash.ne(""))
+            .count()
+            .get_result::<i64>(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        if admin_count > 0 {
+            return Ok(None);
+        }
+
+        let default_admin = user
+            .filter(is_admin.eq(true))
+            .filter(password_hash.eq(""))
+            .order(id)
+            .first::<User>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+        match default_admin {
+            Some(admin) => {
+                diesel::update(user.filter(id.eq(admin.id)))
+                    .set((
+                        username.eq(&msg.username),
+                        password_hash.eq(&msg.password_hash),
+                    ))
+                    .execute(&*connection)
+                    .map_err(actix_web::error::ErrorInternalServerError)?;
+                Ok(Some(User {
+                    username: msg.username,
+                    password_hash: msg.password_hash,
+                    ..admin
+                }))
+            }
+            None => create_user(connection, msg.username, msg.password_hash, true).map(Some),
+        }
+    }
+}
+
+pub struct Delete {
+    pub user_id: UserId,
+}
+impl Message for Delete {
+    type Result = Result<(), Error>;
+}
+impl Handler<Delete> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
+        use schema::favorite_category::dsl as favorite_category;
+        use schema::publication_progress::dsl as publication_progress;
+        use schema::recent_publication::dsl as recent_publication;
+        use schema::session::dsl as session;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        connection
+            .transaction::<_, diesel::result::Error, _>(|| {
+                diesel::delete(session::session.filter(session::user_id.eq(msg.user_id)))
+                    .execute(&*connection)?;
+                diesel::delete(
+                    publication_progress::publication_progress
+                        .filter(publication_progress::user_id.eq(msg.user_id)),
+                )
+                .execute(&*connection)?;
+                diesel::delete(
+                    recent_publication::recent_publication
+                        .filter(recent_publication::user_id.eq(msg.user_id)),
+                )
+                .execute(&*connection)?;
+                diesel::delete(
+                    favorite_category::favorite_category
+                        .filter(favorite_category::user_id.eq(msg.user_id)),
+                )
+                .execute(&*connection)?;
+                diesel::delete(user.filter(id.eq(msg.user_id))).execute(&*connection)?;
+                Ok(())
+            })
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+pub struct GetByUsername {
+    pub username: String,
+}
+impl Message for GetByUsername {
+    type Result = Result<Option<User>, Error>;
+}
+impl Handler<GetByUsername> for DbExecutor {
+    type Result = Result<Option<User>, Error>;
+
+    fn handle(&mut self, msg: GetByUsername, _: &mut Self::Context) -> Self::Result {
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        user.filter(username.eq(&msg.username))
+            .first::<User>(&*connection)
+            .optional()
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Open a new session for a user whose password was checked.
+pub struct Login {
+    pub user_id: UserId,
+}
+impl Message for Login {
+    type Result = Result<Session, Error>;
+}
+impl Handler<Login> for DbExecutor {
+    type Result = Result<Session, Error>;
+
+    fn handle(&mut self, msg: Login, _: &mut Self::Context) -> Self::Result {
+        use schema::session::dsl as session;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        let new_session = Session {
+            token: generate_token(),
+            user_id: msg.user_id,
+            expires_at: Utc::now().naive_utc() + Duration::days(SESSION_DAYS),
+        };
+        diesel::insert_into(session::session)
+            .values(&new_session)
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(new_session)
+    }
+}
+
+pub struct Logout {
+    pub token: String,
+}
+impl Message for Logout {
+    type Result = Result<(), Error>;
+}
+impl Handler<Logout> for DbExecutor {
+    type Result = Result<(), Error>;
+
+    fn handle(&mut self, msg: Logout, _: &mut Self::Context) -> Self::Result {
+        use schema::session::dsl as session;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        diesel::delete(session::session.filter(session::token.eq(msg.token)))
+            .execute(&*connection)
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        Ok(())
+    }
+}
+
+/// Find the user owning an unexpired session token.
+pub struct Authenticate {
+    pub token: String,
+}
+impl Message for Authenticate {
+    type Result = Result<Option<User>, Error>;
+}
+impl Handler<Authenticate> for DbExecutor {
+    type Result = Result<Option<User>, Error>;
+
+    fn handle(&mut self, msg: Authenticate, _: &mut Self::Context) -> Self::Result {
+        use schema::session::dsl as session;
+        let connection: &SqliteConnection = &self.0.get().unwrap();
+        user.filter(
+            id.eq_any(
+                session::session
+                    .filter(session::token.eq(msg.token))
+                    .filter(session::expires_at.gt(Utc::now().naive_utc()))
+                    .select(session::user_id),
+            ),
+        )
+        .first::<User>(&*connection)
+        .optional()
+        .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+fn create_user(
+    connection: &SqliteConnection,
+    the_username: String,
+    the_password_hash: String,
+    admin: bool,
+) -> Result<User, Error> {
+    diesel::insert_into(user)
+        .values(NewUser {
+            username: the_username.clone(),
+            password_hash: the_password_hash,
+            is_admin: admin,
+        })
+        .execute(&*connection)
+        .map_err(actix_web::error::ErrorBadRequest)?;
+
+    user.filter(username.eq(the_username))
+        .first::<User>(&*connection)
+        .map_err(actix_web::error::ErrorInternalServerError)
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .collect()
+}
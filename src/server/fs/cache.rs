@@ -0,0 +1,287 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use config::Config;
+use models::PublicationId;
+use rand;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use walkdir::WalkDir;
+
+const CACHE_LOCATION: &str = "cache";
+/// Prefix of the folders files are extracted to before they are moved into place.
+const EXTRACT_LOCATION: &str = ".extract";
+
+/// Files extracted from a publication to be served to the reader.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheEntry {
+    pub publication_id: PublicationId,
+    /// Bytes taken by the extracted files.
+    pub size: u64,
+    pub last_used: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatus {
+    pub size: u64,
+    pub max_size: u64,
+    /// Most recently used first.
+    pub publications: Vec<CacheEntry>,
+}
+
+#[derive(Debug, Default)]
+struct CacheIndex {
+    entries: HashMap<PublicationId, CacheEntry>,
+}
+
+impl CacheIndex {
+    fn size(&self) -> u64 {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+}
+
+/// Keeps the pages and resources extracted for reading under `PUSTAKA_HOME/cache/<publication id>`
+/// and evicts whole publications, least recently used first, once the cache grows past its size
+/// limit. Shared between the request handlers.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    location: PathBuf,
+    max_size: u64,
+    index: Arc<Mutex<CacheIndex>>,
+    /// Held while extracting into the cache of a publication, so one request doesn't extract
+    /// what another one is still writing and the cache isn't removed from under it.
+    extracting: Arc<Mutex<HashMap<PublicationId, Arc<Mutex<()>>>>>,
+}
+
+impl Cache {
+    /// Pick up whatever an earlier run left in the cache folder.
+    pub fn new(config: &Config) -> Self {
+        let mut location = PathBuf::from(&config.pustaka_home);
+        location.push(CACHE_LOCATION);
+        let cache = Cache {
+            location,
+            max_size: config.cache_size,
+            index: Arc::new(Mutex::new(CacheIndex::default())),
+            extracting: Arc::new(Mutex::new(HashMap::new())),
+        };
+        cache.load();
+        cache
+    }
+
+    fn load(&self) {
+        let folders = match fs::read_dir(&self.location) {
+            Ok(folders) => folders,
+            Err(_) => return,
+        };
+        let mut index = self.lock();
+        for folder in folders.filter_map(|folder| folder.ok()) {
+            let publication_id = match folder
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse().ok())
+            {
+                Some(publication_id) => publication_id,
+                None => continue,
+            };
+            let (size, modified) = folder_usage(&folder.path());
+            index.entries.insert(
+                publication_id,
+                CacheEntry {
+                    publication_id,
+                    size,
+                    last_used: modified,
+                },
+            );
+        }
+    }
+
+    /// Folder the files of a publication are extracted to.
+    pub fn location(&self, publication_id: PublicationId) -> PathBuf {
+        let mut location = self.location.clone();
+        location.push(publication_id.to_string());
+        location
+    }
+
+    /// Path of `name` in the cache of the publication, extracting it with `extract` when it isn't
+    /// there yet. `extract` gets a scratch folder to extract to and returns the path of the file
+    /// it wrote, which is then moved into place as `name`, so a file in the cache is always
+    /// complete. Anything else it writes is thrown away. Names that would end up outside of the
+    /// cache of the publication are refused.
+    pub fn get_or_extract<E, F>(
+        &self,
+        publication_id: PublicationId,
+        name: &Path,
+        extract: F,
+    ) -> Result<PathBuf, E>
+    where
+        F: FnOnce(&Path) -> Result<PathBuf, E>,
+        E: From<io::Error>,
+    {
+        let is_safe = name.components().all(|component| match component {
+            Component::Normal(_) => true,
+            _ => false,
+        });
+        if !is_safe || name.as_os_str().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid file name {}", name.to_string_lossy()),
+            )
+            .into());
+        }
+
+        let location = self.location(publication_id);
+        let cached = location.join(name);
+        if cached.is_file() {
+            self.touch(publication_id);
+            return Ok(cached);
+        }
+
+        let lock = self.extract_lock(publication_id);
+        let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+        // extracted while waiting for the lock
+        if cached.is_file() {
+            self.touch(publication_id);
+            return Ok(cached);
+        }
+
+        let scratch = location.join(format!("{}-{:x}", EXTRACT_LOCATION, rand::random::<u32>()));
+        let res = extract(&scratch).and_then(|extracted| {
+            if let Some(parent) = cached.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&extracted, &cached)?;
+            Ok(cached)
+        });
+        let _ = fs::remove_dir_all(&scratch);
+        let path = res?;
+
+        let (size, _) = folder_usage(&location);
+        {
+            let mut index = self.lock();
+            index.entries.insert(
+                publication_id,
+                CacheEntry {
+                    publication_id,
+                    size,
+                    last_used: Utc::now().naive_utc(),
+                },
+            );
+        }
+        self.evict(publication_id);
+        Ok(path)
+    }
+
+    fn extract_lock(&self, publication_id: PublicationId) -> Arc<Mutex<()>> {
+        self.extracting
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(publication_id)
+            .or_default()
+            .clone()
+    }
+
+    fn touch(&self, publication_id: PublicationId) {
+        if let Some(entry) = self.lock().entries.get_mut(&publication_id) {
+            entry.last_used = Utc::now().naive_utc();
+        }
+    }
+
+    /// Drop the least recently used publications until the cache fits, except for `keep` which
+    /// is being read right now.
+    fn evict(&self, keep: PublicationId) {
+        let mut index = self.lock();
+        let mut candidates: Vec<CacheEntry> = index
+            .entries
+            .values()
+            .filter(|entry| entry.publication_id != keep)
+            .cloned()
+            .collect();
+        candidates.sort_by_key(|entry| entry.last_used);
+
+        let mut size = index.size();
+        for entry in candidates.into_iter() {
+            if size <= self.max_size {
+                break;
+            }
+            let lock = self.extract_lock(entry.publication_id);
+            // being extracted to right now, so not the least recently used after all
+            let _guard = match lock.try_lock() {
+                Ok(guard) => guard,
+                Err(_) => continue,
+            };
+            match remove_folder(&self.location(entry.publication_id)) {
+                Ok(_) => {
+                    size -= entry.size;
+                    index.entries.remove(&entry.publication_id);
+                }
+                Err(err) => eprintln!(
+                    "Unable to evict publication {} from the cache: {}",
+                    entry.publication_id, err
+                ),
+            }
+        }
+    }
+
+    pub fn status(&self) -> CacheStatus {
+        let index = self.lock();
+        let mut publications: Vec<CacheEntry> = index.entries.values().cloned().collect();
+        publications.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        CacheStatus {
+            size: index.size(),
+            max_size: self.max_size,
+            publications,
+        }
+    }
+
+    /// Remove the extracted files of one publication.
+    pub fn remove(&self, publication_id: PublicationId) -> io::Result<()> {
+        let lock = self.extract_lock(publication_id);
+        let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+        let mut index = self.lock();
+        remove_folder(&self.location(publication_id))?;
+        index.entries.remove(&publication_id);
+        Ok(())
+    }
+
+    /// Remove everything in the cache.
+    pub fn purge(&self) -> io::Result<()> {
+        let mut index = self.lock();
+        remove_folder(&self.location)?;
+        index.entries.clear();
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<CacheIndex> {
+        // the index only mirrors the folder, a panic while holding the lock doesn't corrupt it
+        self.index.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+fn remove_folder(path: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(path) {
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+/// Total size of the files in a folder and when the newest of them was written.
+fn folder_usage(path: &Path) -> (u64, NaiveDateTime) {
+    let mut size = 0;
+    let mut modified = NaiveDateTime::default();
+    let files = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file());
+    for metadata in files {
+        size += metadata.len();
+        if let Ok(time) = metadata.modified() {
+            let time = DateTime::<Utc>::from(time).naive_utc();
+            if time > modified {
+                modified = time;
+            }
+        }
+    }
+    (size, modified)
+}
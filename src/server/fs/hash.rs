@@ -0,0 +1,33 @@
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+/// SHA-256 of the file content as a lowercase hex string.
+pub fn sha256(path: &str) -> io::Result<String> {
+    if Path::new(path).is_dir() {
+        return sha256_folder(path);
+    }
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.result()))
+}
+
+/// Folders of images are hashed over the names and content of their files, in name order.
+fn sha256_folder(path: &str) -> io::Result<String> {
+    let mut files = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    files.retain(|file| file.is_file());
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files.iter() {
+        if let Some(name) = file.file_name() {
+            hasher.input(name.to_string_lossy().as_bytes());
+        }
+        io::copy(&mut File::open(file)?, &mut hasher)?;
+    }
+    Ok(format!("{:x}", hasher.result()))
+}
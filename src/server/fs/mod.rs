@@ -1,2 +1,5 @@
+pub mod cache;
 pub mod executor;
+pub mod hash;
+pub mod page;
 pub mod thumbnail;
@@ -0,0 +1,163 @@
+use custom_error::custom_error;
+use image::jpeg::JPEGEncoder;
+use image::{DynamicImage, FilterType, GenericImageView};
+use std::fs;
+use std::path::{Path, PathBuf};
+use webp;
+
+/// Where resized and transcoded pages are kept, inside the cache folder of the publication.
+const DERIVED_LOCATION: &str = "derived";
+const DEFAULT_QUALITY: u8 = 85;
+
+/// Images every browser shows, anything else is transcoded.
+const BROWSER_FORMATS: [&str; 5] = ["jpg", "jpeg", "png", "gif", "webp"];
+
+custom_error! {pub PageImageError
+    Image{ source: image::ImageError} = "Unable to process page image",
+    Io{ source: std::io::Error} = "Unable to access page image",
+    Format{ format: String } = "Unsupported output format {format}, use jpg, png or webp",
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl OutputFormat {
+    pub fn parse(format: &str) -> Result<Self, PageImageError> {
+        match format.to_lowercase().as_str() {
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpeg),
+            "png" => Ok(OutputFormat::Png),
+            "webp" => Ok(OutputFormat::Webp),
+            _ => Err(PageImageError::Format {
+                format: format.to_string(),
+            }),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+        }
+    }
+}
+
+/// How a page image should be served. Pages are only ever made smaller, never scaled up.
+#[derive(Debug, Clone, Default)]
+pub struct PageTransform {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// JPEG and WebP quality from 1 to 100.
+    pub quality: Option<u8>,
+    pub format: Option<OutputFormat>,
+}
+
+impl PageTransform {
+    /// Whether the page can be served as it was extracted.
+    pub fn is_identity(&self, page: &Path) -> bool {
+        self.width.is_none()
+            && self.height.is_none()
+            && self.quality.is_none()
+            && self.format.is_none()
+            && is_browser_format(page)
+    }
+
+    /// Identifies the transform in cache file names and ETags.
+    pub fn key(&self) -> String {
+        format!(
+            "{}x{}-q{}-{}",
+            self.width.unwrap_or(0),
+            self.height.unwrap_or(0),
+            self.quality.unwrap_or(DEFAULT_QUALITY),
+            self.format
+                .map(|format| format.extension())
+                .unwrap_or("auto")
+        )
+    }
+
+    /// PNG pages stay PNG unless asked otherwise, everything else becomes JPEG.
+    fn output_format(&self, page: &Path) -> OutputFormat {
+        self.format
+            .unwrap_or_else(|| match extension(page).as_str() {
+                "png" => OutputFormat::Png,
+                _ => OutputFormat::Jpeg,
+            })
+    }
+
+    /// Path of the derived image of a page, relative to the cache folder of the publication.
+    pub fn derived_name(&self, page: &Path, page_number: usize) -> PathBuf {
+        let mut name = PathBuf::from(DERIVED_LOCATION);
+        name.push(format!(
+            "{}-{}.{}",
+            page_number,
+            self.key(),
+            self.output_format(page).extension()
+        ));
+        name
+    }
+
+    /// Write the transformed page to `output`.
+    pub fn apply(&self, page: &Path, output: &Path) -> Result<PathBuf, PageImageError> {
+        let img = image::open(page)?;
+        let img = self.resize(img);
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match self.output_format(page) {
+            OutputFormat::Jpeg => {
+                let rgb = img.to_rgb();
+                let mut file = fs::File::create(output)?;
+                JPEGEncoder::new_with_quality(
+                    &mut file,
+                    self.quality.unwrap_or(DEFAULT_QUALITY).max(1).min(100),
+                )
+                .encode(
+                    &rgb,
+                    rgb.width(),
+                    rgb.height(),
+                    image::ColorType::RGB(8),
+                )?;
+            }
+            OutputFormat::Png => img.save(output)?,
+            // the image crate can only decode WebP
+            OutputFormat::Webp => {
+                let rgba = img.to_rgba();
+                let quality = self.quality.unwrap_or(DEFAULT_QUALITY).max(1).min(100);
+                let encoded = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height())
+                    .encode(f32::from(quality));
+                fs::write(output, &*encoded)?;
+            }
+        }
+        Ok(output.to_path_buf())
+    }
+
+    fn resize(&self, img: DynamicImage) -> DynamicImage {
+        let (width, height) = (img.width(), img.height());
+        let max_width = self.width.unwrap_or(width).min(width).max(1);
+        let max_height = self.height.unwrap_or(height).min(height).max(1);
+        if max_width == width && max_height == height {
+            return img;
+        }
+        // keeps the aspect ratio, the page fits inside the requested box
+        img.resize(max_width, max_height, FilterType::CatmullRom)
+    }
+}
+
+fn extension(page: &Path) -> String {
+    page.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn is_browser_format(page: &Path) -> bool {
+    let extension = extension(page);
+    BROWSER_FORMATS
+        .iter()
+        .any(|browser_format| *browser_format == extension)
+}
@@ -26,3 +26,25 @@ pub fn resize(file: &str) -> Result<String, ThumbnailError> {
     imageops::resize(&img, nwidth, nheight, image::FilterType::CatmullRom).save(file)?;
     Ok(file.to_string())
 }
+
+/// Difference hash of an image: the image is shrunk to 9x8 grey pixels and every bit records
+/// whether a pixel is brighter than its right neighbour. Covers that look alike end up a few bits
+/// apart regardless of their size or encoding.
+pub fn perceptual_hash(file: &str) -> Result<i64, ThumbnailError> {
+    let img = image::open(file)?;
+    let pixels = imageops::resize(&img.to_luma(), 9, 8, image::FilterType::Triangle);
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let brighter = pixels.get_pixel(x, y).data[0] > pixels.get_pixel(x + 1, y).data[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    // stored as a signed BIGINT, only the bits matter
+    Ok(hash as i64)
+}
+
+/// Number of bits two perceptual hashes differ in.
+pub fn hash_distance(a: i64, b: i64) -> u32 {
+    (a ^ b).count_ones()
+}
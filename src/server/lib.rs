@@ -6,21 +6,38 @@ extern crate serde;
 extern crate serde_derive;
 extern crate actix;
 extern crate actix_web;
+extern crate bcrypt;
+extern crate bytes;
+extern crate cookie;
+extern crate csv;
 extern crate diesel_derive_enum;
 extern crate dotenv;
 extern crate epub;
 extern crate futures;
+extern crate glob;
 #[macro_use]
 extern crate lazy_static;
+extern crate lopdf;
 extern crate mime;
+extern crate notify;
+extern crate percent_encoding;
+extern crate webp;
 extern crate r2d2;
 extern crate r2d2_diesel;
+extern crate rand;
+extern crate regex;
+extern crate serde_json;
+extern crate sevenz_rust;
+extern crate sha2;
+extern crate tar;
+extern crate tokio;
 extern crate unrar;
 extern crate walkdir;
 extern crate zip;
 extern crate custom_error;
 extern crate chrono;
 extern crate image;
+extern crate xml;
 
 pub mod api;
 pub mod config;
@@ -28,8 +45,11 @@ pub mod db;
 #[macro_use]
 pub mod fs;
 pub mod models;
+pub mod password;
 pub mod reader;
 pub mod scan;
 pub mod schema;
 pub mod state;
+pub mod un7z;
+pub mod untar;
 pub mod unzip;
@@ -1,16 +1,27 @@
 extern crate actix;
 extern crate actix_web;
 extern crate diesel;
+extern crate futures;
 extern crate pustaka;
 
 use actix::prelude::*;
 use actix_web::{fs::NamedFile, http, server, App, HttpRequest, Result};
+use futures::{future, Future};
 use http::Method;
-use pustaka::api::{author, category, media_type, publication, tag};
+use pustaka::api::{
+    auth, author, cache, category, category_rule, media_type, publication, scan, setting, tag,
+    user,
+};
 use pustaka::config;
 use pustaka::db::executor::DbExecutor;
-use pustaka::db::setting;
+use pustaka::db::user::CreateAdmin;
+use pustaka::fs::cache::Cache;
 use pustaka::fs::executor::FsExecutor;
+use pustaka::password::{HashPassword, PasswordHasher};
+use pustaka::reader::executor::ReaderExecutor;
+use pustaka::scan::actor::Scanner;
+use pustaka::scan::progress::Progress;
+use pustaka::scan::watch::Watch;
 use pustaka::state::AppState;
 use std::path::PathBuf;
 
@@ -32,20 +43,78 @@ fn main() {
     let config = config::get_config();
     let pool = pustaka::db::create_db_pool(&config.database);
 
+    let db = SyncArbiter::start(1, move || DbExecutor(pool.clone()));
+    let scanner = SyncArbiter::start(5, || Scanner {});
+    let progress = Progress::new();
+    let cache = Cache::new(&config);
+    let password = SyncArbiter::start(2, || PasswordHasher);
+    let reader = SyncArbiter::start(2, || ReaderExecutor);
+
+    // keep the library in sync with the publication folder
+    let watch = if config.watch {
+        Some(
+            Watch::new(
+                config.clone(),
+                db.clone(),
+                scanner.clone(),
+                progress.clone(),
+                cache.clone(),
+            )
+            .start(),
+        )
+    } else {
+        None
+    };
+
     let state = AppState {
-        db: SyncArbiter::start(1, move || DbExecutor(pool.clone())),
+        db,
         fs: SyncArbiter::start(1, move || FsExecutor()),
+        password,
+        reader,
+        scanner,
+        progress,
+        cache,
         config: config,
+        watch,
     };
 
+    // make sure a fresh install has an administrator to log in with
+    if let Some(password) = state.config.admin_password.clone() {
+        let db = state.db.clone();
+        let username = state.config.admin_username.clone();
+        let task = state
+            .password
+            .send(HashPassword { password })
+            .and_then(move |res| match res {
+                Ok(password_hash) => future::Either::A(db.send(CreateAdmin {
+                    username,
+                    password_hash,
+                })),
+                Err(err) => future::Either::B(future::ok(Err(err))),
+            })
+            .map(|res| match res {
+                Ok(Some(admin)) => println!("Created administrator {}", admin.username),
+                Ok(None) => {}
+                Err(err) => println!("Unable to create administrator: {}", err),
+            })
+            .map_err(|err| println!("{:?}", err));
+        Arbiter::spawn(task);
+    }
+
     // start http server
     server::new(move || {
         vec![
+            auth::create_app(state.clone(), "/api/auth"),
+            user::create_app(state.clone(), "/api/user"),
             category::create_app(state.clone(), "/api/category"),
+            category_rule::create_app(state.clone(), "/api/category_rule"),
             publication::create_app(state.clone(), publication::BASE_PATH),
             author::create_app(state.clone(), "/api/author"),
             media_type::create_app(state.clone(), "/api/media_type"),
             tag::create_app(state.clone(), "/api/tag"),
+            scan::create_app(state.clone(), "/api/scan"),
+            setting::create_app(state.clone(), "/api/setting"),
+            cache::create_app(state.clone(), "/api/cache"),
             App::with_state(state.clone())
                 .resource("/{tail:.*}", |r| r.method(Method::GET).f(assets)),
         ]
@@ -1,7 +1,9 @@
 use chrono;
+use diesel::sql_types::{Integer, Text};
 use schema::{
-    author, category, favorite_category, media_type, publication, publication_category,
-    publication_progress, recent_publication, setting, tag,
+    author, category, category_rule, favorite_category, ignored_file, library, media_type,
+    media_type_hint, publication, publication_category, publication_progress, publication_tag,
+    recent_publication, session, setting, tag, user,
 };
 use serde::ser::SerializeStruct;
 use serde::Serialize;
@@ -9,7 +11,26 @@ use serde::Serializer;
 
 pub const CBR: &str = "cbr";
 pub const CBZ: &str = "cbz";
+pub const CB7: &str = "cb7";
+pub const CBT: &str = "cbt";
+/// A folder of page images rather than an archive.
+pub const FOLDER: &str = "folder";
 pub const EPUB: &str = "epub";
+pub const PDF: &str = "pdf";
+/// Media formats that are archives of page images, read through `reader::comic`.
+pub const COMIC_FORMATS: [&str; 5] = [CBR, CBZ, CB7, CBT, FOLDER];
+
+/// Names of the media types the scanner can detect, see `seed.rs`.
+pub const EBOOK: &str = "ebook";
+pub const COMIC: &str = "comic";
+pub const MAGAZINE: &str = "magazine";
+pub const MANGA: &str = "manga";
+
+pub const GLOB: &str = "glob";
+pub const REGEX: &str = "regex";
+
+/// Category for publications that neither a rule nor the category names could place.
+pub const UNCATEGORIZED: &str = "Uncategorized";
 
 pub type CategoryId = i32;
 
@@ -28,6 +49,28 @@ pub struct Category {
     pub parent_id: Option<i32>,
 }
 
+#[derive(Debug, Insertable, Deserialize)]
+#[table_name = "category_rule"]
+pub struct NewCategoryRule {
+    pub pattern: String,
+    pub kind: String,
+    pub category_id: CategoryId,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Puts every publication whose path inside its library matches the glob or regex pattern in the
+/// category. Rules with a higher priority are checked first.
+#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, AsChangeset, Clone)]
+#[table_name = "category_rule"]
+pub struct CategoryRule {
+    pub id: i32,
+    pub pattern: String,
+    pub kind: String,
+    pub category_id: CategoryId,
+    pub priority: i32,
+}
+
 #[derive(Debug, Insertable, Deserialize)]
 #[table_name = "media_type"]
 pub struct NewMediaType {
@@ -36,13 +79,29 @@ pub struct NewMediaType {
 
 pub type MediaTypeId = i32;
 
-#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, AsChangeset)]
+#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, AsChangeset, Clone)]
 #[table_name = "media_type"]
 pub struct MediaType {
     pub id: MediaTypeId,
     pub name: String,
 }
 
+#[derive(Debug, Insertable, Deserialize)]
+#[table_name = "media_type_hint"]
+pub struct NewMediaTypeHint {
+    pub folder: String,
+    pub media_type_id: MediaTypeId,
+}
+
+/// Publications with a folder of this name anywhere in their path get this media type.
+#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Clone)]
+#[table_name = "media_type_hint"]
+pub struct MediaTypeHint {
+    pub id: i32,
+    pub folder: String,
+    pub media_type_id: MediaTypeId,
+}
+
 #[derive(Debug, Insertable, Deserialize)]
 #[table_name = "author"]
 pub struct NewAuthor {
@@ -69,11 +128,19 @@ pub struct NewPublication {
     pub thumbnail: Option<String>,
     pub timestamp: Option<chrono::NaiveDateTime>,
     pub file: String,
+    pub language: Option<String>,
+    pub series: Option<String>,
+    pub issue: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified: Option<chrono::NaiveDateTime>,
+    pub file_hash: Option<String>,
 }
 
 pub type PublicationId = i32;
 
-#[derive(Identifiable, Debug, Queryable, Deserialize, Associations, AsChangeset, Clone)]
+#[derive(
+    Identifiable, Debug, Queryable, QueryableByName, Deserialize, Associations, AsChangeset, Clone,
+)]
 #[belongs_to(MediaType)]
 #[belongs_to(Author)]
 #[table_name = "publication"]
@@ -87,6 +154,17 @@ pub struct Publication {
     pub thumbnail: Option<String>,
     pub file: String,
     pub timestamp: Option<chrono::NaiveDateTime>,
+    pub language: Option<String>,
+    pub series: Option<String>,
+    pub issue: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified: Option<chrono::NaiveDateTime>,
+    pub file_hash: Option<String>,
+    #[serde(default)]
+    pub missing: bool,
+    /// Perceptual hash of the thumbnail, see `fs::thumbnail::perceptual_hash`.
+    #[serde(default)]
+    pub thumbnail_hash: Option<i64>,
 }
 
 impl Publication {
@@ -104,7 +182,7 @@ impl Serialize for Publication {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Publication", 8)?;
+        let mut state = serializer.serialize_struct("Publication", 12)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("isbn", &self.isbn)?;
         state.serialize_field("title", &self.title)?;
@@ -112,6 +190,10 @@ impl Serialize for Publication {
         state.serialize_field("author_id", &self.author_id)?;
         state.serialize_field("file", &self.file)?;
         state.serialize_field("media_format", &self.media_format)?;
+        state.serialize_field("language", &self.language)?;
+        state.serialize_field("series", &self.series)?;
+        state.serialize_field("issue", &self.issue)?;
+        state.serialize_field("missing", &self.missing)?;
         match self.has_thumbnail() {
             true => state.serialize_field("has_thumbnail", &true)?,
             false => state.serialize_field("has_thumbnail", &false)?,
@@ -144,34 +226,149 @@ pub struct Tag {
     pub name: String,
 }
 
+#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Associations, Insertable)]
+#[belongs_to(Tag, foreign_key = "tag_id")]
+#[table_name = "publication_tag"]
+#[primary_key(publication_id, tag_id)]
+pub struct PublicationTag {
+    pub publication_id: PublicationId,
+    pub tag_id: TagId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaggedPublication {
+    #[serde(flatten)]
+    pub publication: Publication,
+    pub tags: Vec<Tag>,
+}
+
 #[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Associations, Insertable)]
 #[belongs_to(Category, foreign_key = "category_id")]
 #[table_name = "favorite_category"]
-#[primary_key(category_id)]
+#[primary_key(user_id, category_id)]
 pub struct FavoriteCategory {
+    pub user_id: UserId,
     pub category_id: CategoryId,
 }
 
 #[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Associations, Insertable)]
 #[table_name = "publication_progress"]
-#[primary_key(publication_id)]
+#[primary_key(user_id, publication_id)]
 pub struct PublicationProgress {
+    pub user_id: UserId,
     pub publication_id: i32,
     pub progress: f32,
 }
 
 #[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Associations, Insertable)]
 #[table_name = "recent_publication"]
-#[primary_key(publication_id)]
+#[primary_key(user_id, publication_id)]
 pub struct RecentPublication {
+    pub user_id: UserId,
     pub publication_id: i32,
     pub timestamp: Option<chrono::NaiveDateTime>,
 }
 
+/// A file that was merged into another publication and shouldn't be imported again.
+#[derive(Identifiable, Debug, Queryable, Serialize, Insertable)]
+#[table_name = "ignored_file"]
+#[primary_key(file_hash)]
+pub struct IgnoredFile {
+    pub file_hash: String,
+    pub file: String,
+    pub timestamp: Option<chrono::NaiveDateTime>,
+}
+
 #[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, Insertable)]
 #[table_name = "setting"]
 #[primary_key(setting_id)]
 pub struct Setting {
     pub setting_id: i32,
-    pub publication_path: Option<String>,
+    /// Fuzzy matches between a category name and the file path scoring below this go to the
+    /// library's category, or `UNCATEGORIZED` when it has none.
+    pub min_category_score: f64,
+}
+
+impl Default for Setting {
+    fn default() -> Self {
+        Setting {
+            setting_id: 1,
+            min_category_score: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[table_name = "library"]
+pub struct NewLibrary {
+    pub name: String,
+    pub path: String,
+    pub media_type_id: MediaTypeId,
+    pub category_id: Option<CategoryId>,
+}
+
+pub type LibraryId = i32;
+
+/// A root folder the scanner imports publications from. Publications found in it get its media
+/// type unless the scanner detects another one, and its category when none of the category names
+/// match the file path.
+#[derive(Identifiable, Debug, Queryable, Serialize, Deserialize, AsChangeset, Clone)]
+#[table_name = "library"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct Library {
+    pub id: LibraryId,
+    pub name: String,
+    pub path: String,
+    pub media_type_id: MediaTypeId,
+    pub category_id: Option<CategoryId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct ContentMatch {
+    #[sql_type = "Integer"]
+    pub publication_id: PublicationId,
+    #[sql_type = "Text"]
+    pub title: String,
+    #[sql_type = "Integer"]
+    pub page: i32,
+    #[sql_type = "Text"]
+    pub snippet: String,
+}
+
+pub type UserId = i32;
+
+#[derive(Debug, Insertable)]
+#[table_name = "user"]
+pub struct NewUser {
+    pub username: String,
+    pub password_hash: String,
+    pub is_admin: bool,
+}
+
+#[derive(Identifiable, Debug, Queryable, Serialize, Clone)]
+#[table_name = "user"]
+pub struct User {
+    pub id: UserId,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub is_admin: bool,
+}
+
+#[derive(Identifiable, Debug, Queryable, Serialize, Associations, Insertable)]
+#[belongs_to(User, foreign_key = "user_id")]
+#[table_name = "session"]
+#[primary_key(token)]
+pub struct Session {
+    pub token: String,
+    pub user_id: UserId,
+    pub expires_at: chrono::NaiveDateTime,
 }
@@ -0,0 +1,56 @@
+use actix::{prelude::*, Actor, SyncContext};
+use actix_web::Error;
+use bcrypt;
+
+lazy_static! {
+    /// Checked against when the user doesn't exist, so that a wrong username takes as long as a
+    /// wrong password.
+    static ref DUMMY_HASH: String =
+        bcrypt::hash("pustaka", bcrypt::DEFAULT_COST).expect("Unable to hash the dummy password");
+}
+
+/// Hashing and checking passwords takes a good part of a second on purpose, it gets its own
+/// threads so it doesn't hold up the database or the HTTP workers.
+pub struct PasswordHasher;
+
+impl Actor for PasswordHasher {
+    type Context = SyncContext<Self>;
+}
+
+pub struct HashPassword {
+    pub password: String,
+}
+impl Message for HashPassword {
+    type Result = Result<String, Error>;
+}
+impl Handler<HashPassword> for PasswordHasher {
+    type Result = Result<String, Error>;
+
+    fn handle(&mut self, msg: HashPassword, _: &mut Self::Context) -> Self::Result {
+        bcrypt::hash(&msg.password, bcrypt::DEFAULT_COST)
+            .map_err(actix_web::error::ErrorInternalServerError)
+    }
+}
+
+/// Whether `password` matches `hash`. Without a hash, e.g. for an unknown user, the password is
+/// checked against a dummy hash and never matches.
+pub struct VerifyPassword {
+    pub password: String,
+    pub hash: Option<String>,
+}
+impl Message for VerifyPassword {
+    type Result = bool;
+}
+impl Handler<VerifyPassword> for PasswordHasher {
+    type Result = bool;
+
+    fn handle(&mut self, msg: VerifyPassword, _: &mut Self::Context) -> Self::Result {
+        match msg.hash {
+            Some(hash) => bcrypt::verify(&msg.password, &hash).unwrap_or(false),
+            None => {
+                let _ = bcrypt::verify(&msg.password, &DUMMY_HASH);
+                false
+            }
+        }
+    }
+}
@@ -1,12 +1,30 @@
 use actix_web::ResponseError;
-use config::Config;
-use models::{Publication, CBR, CBZ};
-use reader::models::Data;
+use chrono::NaiveDateTime;
+use fs::cache::Cache;
+use image::bmp::BMPDecoder;
+use image::jpeg::JPEGDecoder;
+use image::png::PNGDecoder;
+use image::tiff::TIFFDecoder;
+use image::webp::WebpDecoder;
+use image::{self, gif, ImageDecoder, ImageFormat};
+use models::{Publication, CB7, CBR, CBT, CBZ, FOLDER};
+use rand;
+use reader::comic_info::{self, ComicInfo};
+use reader::models::{Data, PageInfo};
+use serde_json;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
 use std::error::Error;
 use std::fmt;
-use std::io;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::iter::Peekable;
+use std::path::{Component, Path, PathBuf};
+use std::str::Chars;
+use un7z;
 use unrar::Archive;
+use untar;
 use unzip;
 
 #[derive(Debug)]
@@ -20,6 +38,12 @@ pub enum ComicError {
     GenericError(String),
 }
 
+impl From<io::Error> for ComicError {
+    fn from(error: io::Error) -> Self {
+        ComicError::IOError(error)
+    }
+}
+
 impl fmt::Display for ComicError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -38,30 +62,37 @@ impl Error for ComicError {}
 
 impl ResponseError for ComicError {}
 
-const EXTRACT_LOCATION: &str = "cache";
+/// Where a RAR archive is extracted before the requested page is picked out of it. Every extraction
+/// gets its own folder so that concurrent requests don't trip over each other.
+const SCRATCH_LOCATION: &str = ".extract";
 
-pub fn open(config: &Config, the_publication: &Publication) -> Result<Data, ComicError> {
-    use reader::comic::ComicError::*;
+/// Where the page list with the page dimensions is kept, inside the cache of the publication.
+const MANIFEST_NAME: &str = "pages.json";
 
-    match the_publication.media_format.as_ref() {
-        CBR => open_cbr(config, the_publication),
-        CBZ => open_cbz(the_publication),
-        _ => Err(InvalidMediaFormatError),
+/// The page list kept in the cache, with the file it was measured from so that it's measured again
+/// once the file changes.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    file_size: Option<i64>,
+    file_modified: Option<NaiveDateTime>,
+    pages: Vec<PageInfo>,
+}
+
+impl Manifest {
+    fn is_for(&self, the_publication: &Publication) -> bool {
+        self.file_size == the_publication.file_size
+            && self.file_modified == the_publication.file_modified
     }
 }
 
-fn open_cbr(config: &Config, the_publication: &Publication) -> Result<Data, ComicError> {
-    use reader::comic::ComicError::*;
-    let mut extract_location = PathBuf::from(config.pustaka_home.clone());
-    extract_location.push(EXTRACT_LOCATION);
-    extract_location.push(the_publication.id.to_string());
-    let extract_location = extract_location
-        .to_str()
-        .ok_or(GenericError("Extract location is invalid".to_string()))?;
+/// Files in an archive that are pages, anything else (ComicInfo.xml, Thumbs.db, .nfo files,
+/// folders) isn't shown to the reader.
+const PAGE_EXTENSIONS: [&str; 10] = [
+    "jpg", "jpeg", "png", "gif", "webp", "bmp", "tif", "tiff", "jp2", "j2k",
+];
 
-    let open_archive = Archive::new(the_publication.file.clone())
-        .extract_to(extract_location.to_string())
-        .map_err(|_err| RarError)?;
+pub fn open(the_publication: &Publication) -> Result<Data, ComicError> {
+    let names = page_names(&the_publication.file, &the_publication.media_format)?;
 
     Ok(Data {
         id: the_publication.id,
@@ -72,80 +103,510 @@ fn open_cbr(config: &Config, the_publication: &Publication) -> Result<Data, Comi
         has_thumbnail: the_publication.has_thumbnail().clone(),
         file: the_publication.file.clone(),
         media_format: the_publication.media_format.clone(),
-        total_pages: open_archive.count(),
+        total_pages: names.len(),
     })
 }
 
-fn open_cbz(the_publication: &Publication) -> Result<Data, ComicError> {
+/// Names of the pages inside the archive in reading order. Page numbers everywhere else index into
+/// this list.
+pub fn page_names(file: &str, media_format: &str) -> Result<Vec<String>, ComicError> {
     use reader::comic::ComicError::*;
 
-    let count = unzip::count(&the_publication.file).map_err(|_| ZipError)?;
+    let names = match media_format {
+        CBR => Archive::new(file.to_string())
+            .list()
+            .map_err(|_err| RarError)?
+            .map(|entry| entry.map(|entry| entry.filename).map_err(|_err| RarError))
+            .collect::<Result<Vec<String>, ComicError>>()?,
+        CBZ => unzip::names(file).map_err(|_| ZipError)?,
+        CB7 => un7z::names(file).map_err(IOError)?,
+        CBT => untar::names(file).map_err(IOError)?,
+        FOLDER => folder_names(file).map_err(IOError)?,
+        _ => return Err(InvalidMediaFormatError),
+    };
 
-    Ok(Data {
-        id: the_publication.id,
-        isbn: the_publication.isbn.clone(),
-        title: the_publication.title.clone(),
-        media_type_id: the_publication.media_type_id,
-        author_id: the_publication.author_id,
-        has_thumbnail: the_publication.has_thumbnail().clone(),
-        file: the_publication.file.clone(),
-        media_format: the_publication.media_format.clone(),
-        total_pages: count,
-    })
+    let mut names: Vec<String> = names.into_iter().filter(|name| is_page(name)).collect();
+    names.sort_by(|a, b| natural_cmp(a, b).then_with(|| a.cmp(b)));
+    Ok(names)
+}
+
+fn folder_names(folder: &str) -> io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Whether a file is shown to the reader as a page.
+pub fn is_page(name: &str) -> bool {
+    let path = Path::new(name);
+    // archives made from the current folder list their entries as `./001.jpg`
+    let hidden = path.components().any(|component| match component {
+        Component::Normal(component) => {
+            let component = component.to_string_lossy();
+            component.starts_with('.') || component == "__MACOSX"
+        }
+        _ => false,
+    });
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    !hidden
+        && PAGE_EXTENSIONS
+            .iter()
+            .any(|page_extension| *page_extension == extension)
 }
 
-fn generate_extract_location(
-    config: &Config,
+/// Compares runs of digits by their value so that `page2.jpg` comes before `page10.jpg`, the rest
+/// is compared ignoring case.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let ordering = match (a.peek().cloned(), b.peek().cloned()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(x), Some(y)) => {
+                a.next();
+                b.next();
+                x.to_lowercase().cmp(y.to_lowercase())
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn take_number(chars: &mut Peekable<Chars>) -> String {
+    let mut number = String::new();
+    while let Some(digit) = chars.peek().cloned().filter(|c| c.is_ascii_digit()) {
+        number.push(digit);
+        chars.next();
+    }
+    number
+}
+
+fn page_name(the_publication: &Publication, page_number: usize) -> Result<String, ComicError> {
+    page_names(&the_publication.file, &the_publication.media_format)?
+        .into_iter()
+        .nth(page_number)
+        .ok_or(ComicError::PageNotFound)
+}
+
+/// Path of the page in the cache, only the requested page is extracted. Pages of a folder are
+/// served from the folder itself.
+pub fn page(
+    cache: &Cache,
     the_publication: &Publication,
-) -> Result<String, ComicError> {
-    let mut extract_location = PathBuf::from(config.pustaka_home.clone());
-    extract_location.push(EXTRACT_LOCATION);
-    extract_location.push(the_publication.id.to_string());
+    page_number: usize,
+) -> Result<PathBuf, ComicError> {
+    let file = &the_publication.file;
+    let name = page_name(the_publication, page_number)?;
+    let media_format = &the_publication.media_format;
+    if media_format == FOLDER {
+        return Ok(Path::new(file).join(name));
+    }
+    cache.get_or_extract(the_publication.id, Path::new(&name), |location| {
+        let location = location.to_str().ok_or(ComicError::GenericError(
+            "Unable to get extract location path".to_string(),
+        ))?;
+        extract_entry(file, media_format, &name, location).map(PathBuf::from)
+    })
+}
 
-    extract_location
-        .to_str()
+/// Every page with its size, so the reader can lay out spreads before loading the images. The list
+/// is worked out once and kept in the cache until the file changes.
+pub fn pages(cache: &Cache, the_publication: &Publication) -> Result<Vec<PageInfo>, ComicError> {
+    match manifest(cache, the_publication)? {
+        Some(manifest) if manifest.is_for(the_publication) => return Ok(manifest.pages),
+        _ => {}
+    }
+
+    // the pages extracted from the old file are just as stale as the list
+    cache
+        .remove(the_publication.id)
+        .map_err(ComicError::IOError)?;
+    manifest(cache, the_publication)?
+        .map(|manifest| manifest.pages)
         .ok_or(ComicError::GenericError(
-            "Unable to get extract location path".to_string(),
+            "Unable to read the page list".to_string(),
         ))
-        .map(|path| path.to_string())
 }
 
-pub fn page(
-    config: &Config,
+/// The manifest kept in the cache, measuring the pages when there is none. Manifests that don't
+/// parse, e.g. those written before the file was recorded in them, come back as `None`.
+fn manifest(cache: &Cache, the_publication: &Publication) -> Result<Option<Manifest>, ComicError> {
+    use reader::comic::ComicError::*;
+
+    let manifest =
+        cache.get_or_extract(the_publication.id, Path::new(MANIFEST_NAME), |location| {
+            let manifest = Manifest {
+                file_size: the_publication.file_size,
+                file_modified: the_publication.file_modified,
+                pages: measure_pages(the_publication, location)?,
+            };
+            let content =
+                serde_json::to_vec(&manifest).map_err(|err| GenericError(err.to_string()))?;
+            fs::create_dir_all(location).map_err(IOError)?;
+            let manifest = location.join(MANIFEST_NAME);
+            fs::write(&manifest, content).map_err(IOError)?;
+            Ok::<_, ComicError>(manifest)
+        })?;
+
+    let content = File::open(manifest).map_err(IOError)?;
+    Ok(serde_json::from_reader(content).ok())
+}
+
+/// Read the dimensions of the pages from their headers, going through the archive once without
+/// extracting it. Pages the image crate can't read are listed without dimensions.
+fn measure_pages(
     the_publication: &Publication,
-    page_number: usize,
-) -> Result<String, ComicError> {
-    let extract_location = &generate_extract_location(config, the_publication)?;
-    match the_publication.media_format.as_ref() {
-        CBR => page_cbr(&the_publication.file, page_number, extract_location),
-        CBZ => page_cbz(&the_publication.file, page_number, extract_location),
-        _ => Err(ComicError::InvalidMediaFormatError),
+    location: &Path,
+) -> Result<Vec<PageInfo>, ComicError> {
+    use reader::comic::ComicError::*;
+    let file = &the_publication.file;
+    let names = page_names(file, &the_publication.media_format)?;
+    let mut dimensions: HashMap<String, (u32, u32)> = HashMap::new();
+    {
+        let mut measure = |name: &str, reader: &mut Read| {
+            if !is_page(name) {
+                return;
+            }
+            if let Some(page_dimensions) = image_dimensions(reader) {
+                dimensions.insert(name.to_string(), page_dimensions);
+            }
+        };
+        match the_publication.media_format.as_ref() {
+            CBR => measure_pages_cbr(file, location, &mut measure)?,
+            CBZ => unzip::read_files(file, measure).map_err(|_| ZipError)?,
+            CB7 => un7z::read_files(file, measure).map_err(IOError)?,
+            CBT => untar::read_files(file, measure).map_err(IOError)?,
+            FOLDER => {
+                for name in names.iter() {
+                    let mut page = File::open(Path::new(file).join(name)).map_err(IOError)?;
+                    measure(name, &mut page);
+                }
+            }
+            _ => return Err(InvalidMediaFormatError),
+        }
     }
+
+    Ok(names
+        .into_iter()
+        .enumerate()
+        .map(|(number, name)| {
+            let dimensions = dimensions.get(&name).cloned();
+            PageInfo {
+                number,
+                width: dimensions.map(|(width, _)| width),
+                height: dimensions.map(|(_, height)| height),
+                spread: dimensions
+                    .map(|(width, height)| width > height)
+                    .unwrap_or(false),
+                name,
+            }
+        })
+        .collect())
 }
 
-pub fn page_cbz(
+/// RAR entries can only be extracted to disk, each one is removed again once it is measured so
+/// no more than one page is on disk at a time.
+fn measure_pages_cbr(
     file: &str,
+    location: &Path,
+    measure: &mut FnMut(&str, &mut Read),
+) -> Result<(), ComicError> {
+    use reader::comic::ComicError::*;
+    let scratch = location.join(format!("{}-pages", SCRATCH_LOCATION));
+    let scratch_str = scratch
+        .to_str()
+        .ok_or(GenericError(
+            "Unable to get extract location path".to_string(),
+        ))?
+        .to_string();
+
+    let res = Archive::new(file.to_string())
+        .extract_to(scratch_str)
+        .map_err(|_err| RarError)
+        .map(|open_archive| {
+            for entry in open_archive.filter_map(|entry| entry.ok()) {
+                let path = scratch.join(&entry.filename);
+                if let Ok(mut page) = File::open(&path) {
+                    measure(&entry.filename, &mut page);
+                }
+                let _ = fs::remove_file(&path);
+            }
+        });
+    let _ = fs::remove_dir_all(&scratch);
+    res
+}
+
+/// Width and height of an image, only its header is read.
+fn image_dimensions(reader: &mut Read) -> Option<(u32, u32)> {
+    let mut head = Vec::new();
+    reader.take(12).read_to_end(&mut head).ok()?;
+    // the image crate only knows WebP by its extension
+    let format = if head.len() == 12 && &head[..4] == b"RIFF" && &head[8..] == b"WEBP" {
+        ImageFormat::WEBP
+    } else {
+        image::guess_format(&head).ok()?
+    };
+    let mut reader = BufReader::new(io::Cursor::new(head).chain(reader));
+    let (width, height) = match format {
+        ImageFormat::JPEG => JPEGDecoder::new(reader).ok()?.dimensions(),
+        ImageFormat::PNG => PNGDecoder::new(reader).ok()?.dimensions(),
+        ImageFormat::GIF => gif::Decoder::new(reader).ok()?.dimensions(),
+        ImageFormat::WEBP => WebpDecoder::new(reader).ok()?.dimensions(),
+        // these decoders need to seek, the whole image has to be read
+        ImageFormat::BMP | ImageFormat::TIFF => {
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content).ok()?;
+            let content = io::Cursor::new(content);
+            match format {
+                ImageFormat::BMP => BMPDecoder::new(content).ok()?.dimensions(),
+                _ => TIFFDecoder::new(content).ok()?.dimensions(),
+            }
+        }
+        _ => return None,
+    };
+    Some((width as u32, height as u32))
+}
+
+/// Extract a page to `extract_location`, e.g. to make a thumbnail out of the first page.
+pub fn extract_page(
+    file: &str,
+    media_format: &str,
     page_number: usize,
     extract_location: &str,
 ) -> Result<String, ComicError> {
-    unzip::unzip_nth(file, extract_location, page_number).map_err(|_| ComicError::ZipError)
+    let name = page_names(file, media_format)?
+        .into_iter()
+        .nth(page_number)
+        .ok_or(ComicError::PageNotFound)?;
+    extract_entry(file, media_format, &name, extract_location)
 }
 
-pub fn page_cbr(
+/// Extract a single file of the archive, `name` is its path inside the archive.
+fn extract_entry(
     file: &str,
-    page_number: usize,
+    media_format: &str,
+    name: &str,
     extract_location: &str,
 ) -> Result<String, ComicError> {
+    use reader::comic::ComicError::*;
+    match media_format {
+        CBR => extract_entry_cbr(file, name, extract_location),
+        CBZ => unzip::unzip_by_name(file, extract_location, name).map_err(|_| ZipError),
+        CB7 => un7z::un7z_by_name(file, extract_location, name).map_err(IOError),
+        CBT => untar::untar_by_name(file, extract_location, name).map_err(IOError),
+        FOLDER => copy_from_folder(file, extract_location, name).map_err(IOError),
+        _ => Err(InvalidMediaFormatError),
+    }
+}
+
+/// Pages of a folder are copied, callers like the thumbnail resize the extracted file in place.
+fn copy_from_folder(folder: &str, extract_location: &str, name: &str) -> io::Result<String> {
+    fs::create_dir_all(extract_location)?;
+    let target = Path::new(extract_location).join(name);
+    fs::copy(Path::new(folder).join(name), &target)?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// RAR archives are usually solid, so the entries before the requested one are decompressed and
+/// written out as well.
+fn extract_entry_cbr(file: &str, name: &str, extract_location: &str) -> Result<String, ComicError> {
+    use reader::comic::ComicError::*;
+    let open_archive = Archive::new(file.to_string())
+        .extract_to(extract_location.to_string())
+        .map_err(|_err| RarError)?;
+
+    for entry in open_archive {
+        match entry {
+            Ok(ref entry) if entry.filename == name => {
+                return Ok(format!("{}/{}", extract_location, entry.filename))
+            }
+            Ok(_) => continue,
+            Err(_err) => return Err(PageError),
+        }
+    }
+    Err(PageNotFound)
+}
+
+pub fn comic_info(the_publication: &Publication) -> Result<Option<ComicInfo>, ComicError> {
+    match the_publication.media_format.as_ref() {
+        CBR => {
+            // previews all use the same placeholder id, so the name has to be unique on its own
+            let mut extract_location = env::temp_dir();
+            extract_location.push(format!("pustaka-comic-info-{:x}", rand::random::<u64>()));
+            let res = extract_location
+                .to_str()
+                .ok_or(ComicError::GenericError(
+                    "Unable to get extract location path".to_string(),
+                ))
+                .and_then(|location| comic_info_cbr(&the_publication.file, location));
+            let _ = fs::remove_dir_all(&extract_location);
+            res
+        }
+        CBZ => parse_comic_info(
+            unzip::read_comic_info(&the_publication.file).map_err(|_| ComicError::ZipError)?,
+        ),
+        CB7 => parse_comic_info(
+            un7z::read_comic_info(&the_publication.file).map_err(ComicError::IOError)?,
+        ),
+        CBT => parse_comic_info(
+            untar::read_comic_info(&the_publication.file).map_err(ComicError::IOError)?,
+        ),
+        FOLDER => {
+            parse_comic_info(folder_comic_info(&the_publication.file).map_err(ComicError::IOError)?)
+        }
+        _ => Err(ComicError::InvalidMediaFormatError),
+    }
+}
+
+fn parse_comic_info(content: Option<Vec<u8>>) -> Result<Option<ComicInfo>, ComicError> {
+    match content {
+        Some(content) => comic_info::parse(content.as_slice())
+            .map(Some)
+            .map_err(|err| ComicError::GenericError(err.to_string())),
+        None => Ok(None),
+    }
+}
+
+fn folder_comic_info(folder: &str) -> io::Result<Option<Vec<u8>>> {
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        if comic_info::is_comic_info(&entry.file_name().to_string_lossy()) {
+            return fs::read(entry.path()).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+fn comic_info_cbr(file: &str, extract_location: &str) -> Result<Option<ComicInfo>, ComicError> {
     use reader::comic::ComicError::*;
     let mut open_archive = Archive::new(file.to_string())
         .extract_to(extract_location.to_string())
         .map_err(|_err| RarError)?;
 
-    match open_archive.nth(page_number) {
-        Some(item) => match item {
-            Ok(entry) => Ok(format!("{}/{}", extract_location, entry.filename)),
-            Err(_err) => Err(PageError),
-        },
-        None => Err(PageNotFound),
+    let entry = open_archive.find(|entry| match entry {
+        Ok(entry) => comic_info::is_comic_info(&entry.filename),
+        Err(_) => false,
+    });
+
+    match entry {
+        Some(Ok(entry)) => {
+            let path = format!("{}/{}", extract_location, entry.filename);
+            let content = File::open(path).map_err(IOError)?;
+            comic_info::parse(content)
+                .map(Some)
+                .map_err(|err| GenericError(err.to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_cmp_orders_numbers_by_value() {
+        assert_eq!(natural_cmp("page2.jpg", "page10.jpg"), Ordering::Less);
+        assert_eq!(natural_cmp("page10.jpg", "page2.jpg"), Ordering::Greater);
+        assert_eq!(natural_cmp("page002.jpg", "page10.jpg"), Ordering::Less);
+        assert_eq!(natural_cmp("page02.jpg", "page2.jpg"), Ordering::Equal);
+    }
+
+    #[test]
+    fn natural_cmp_ignores_case() {
+        assert_eq!(natural_cmp("Page1.jpg", "page1.jpg"), Ordering::Equal);
+        assert_eq!(natural_cmp("a.jpg", "B.jpg"), Ordering::Less);
+    }
+
+    #[test]
+    fn natural_cmp_puts_prefixes_first() {
+        assert_eq!(natural_cmp("page", "page1"), Ordering::Less);
+        assert_eq!(natural_cmp("", ""), Ordering::Equal);
+    }
+
+    #[test]
+    fn natural_cmp_sorts_pages() {
+        let mut names = vec![
+            "10.png",
+            "Chapter 2/1.png",
+            "1.png",
+            "chapter 10/1.png",
+            "2.png",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b).then_with(|| a.cmp(b)));
+        assert_eq!(
+            names,
+            vec![
+                "1.png",
+                "2.png",
+                "10.png",
+                "Chapter 2/1.png",
+                "chapter 10/1.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn is_page_accepts_images() {
+        assert!(is_page("001.jpg"));
+        assert!(is_page("chapter 1/001.JPEG"));
+        assert!(is_page("cover.webp"));
+        assert!(is_page("scan.jp2"));
+    }
+
+    #[test]
+    fn is_page_accepts_entries_of_the_current_folder() {
+        assert!(is_page("./001.jpg"));
+        assert!(is_page("./chapter 1/001.jpg"));
+        assert!(!is_page("./.001.jpg"));
+    }
+
+    #[test]
+    fn is_page_skips_other_files() {
+        assert!(!is_page("ComicInfo.xml"));
+        assert!(!is_page("Thumbs.db"));
+        assert!(!is_page("release.nfo"));
+        assert!(!is_page("chapter 1/"));
+        assert!(!is_page("no_extension"));
+    }
+
+    #[test]
+    fn image_dimensions_reads_the_header() {
+        let pixels = vec![0; 3 * 2 * 3];
+        let mut png = Vec::new();
+        image::png::PNGEncoder::new(&mut png)
+            .encode(&pixels, 3, 2, image::ColorType::RGB(8))
+            .unwrap();
+        let mut jpeg = Vec::new();
+        image::jpeg::JPEGEncoder::new(&mut jpeg)
+            .encode(&pixels, 3, 2, image::ColorType::RGB(8))
+            .unwrap();
+
+        assert_eq!(image_dimensions(&mut png.as_slice()), Some((3, 2)));
+        assert_eq!(image_dimensions(&mut jpeg.as_slice()), Some((3, 2)));
+        assert_eq!(image_dimensions(&mut "not an image".as_bytes()), None);
+    }
+
+    #[test]
+    fn is_page_skips_hidden_files() {
+        assert!(!is_page(".001.jpg"));
+        assert!(!is_page("__MACOSX/001.jpg"));
+        assert!(!is_page("chapter 1/._001.jpg"));
     }
 }
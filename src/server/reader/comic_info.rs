@@ -0,0 +1,129 @@
+use models::{COMIC, MANGA};
+use reader::models::Metadata;
+use std::io::Read;
+use std::path::Path;
+use xml::reader::{EventReader, XmlEvent};
+
+const COMIC_INFO_FILE: &str = "ComicInfo.xml";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ComicInfo {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub writer: Option<String>,
+    pub publisher: Option<String>,
+    pub summary: Option<String>,
+    pub page_count: Option<usize>,
+    pub manga: bool,
+    pub pages: Vec<ComicPageInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ComicPageInfo {
+    pub image: usize,
+    pub page_type: Option<String>,
+    pub double_page: bool,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ComicInfo {
+    pub fn to_metadata(&self) -> Metadata {
+        let title = match (&self.title, &self.series, &self.number) {
+            (Some(title), _, _) => Some(title.clone()),
+            (None, Some(series), Some(number)) => Some(format!("{} #{}", series, number)),
+            (None, Some(series), None) => Some(series.clone()),
+            (None, None, _) => None,
+        };
+        let author = self.writer.as_ref().and_then(|writer| {
+            writer
+                .split(',')
+                .map(|name| name.trim())
+                .find(|name| !name.is_empty())
+                .map(|name| name.to_string())
+        });
+
+        Metadata {
+            title,
+            author,
+            series: self.series.clone(),
+            issue: self.number.clone(),
+            media_type: Some(match self.manga {
+                true => MANGA.to_string(),
+                false => COMIC.to_string(),
+            }),
+            ..Metadata::default()
+        }
+    }
+}
+
+pub fn is_comic_info(file_name: &str) -> bool {
+    Path::new(file_name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.eq_ignore_ascii_case(COMIC_INFO_FILE))
+        .unwrap_or(false)
+}
+
+pub fn parse<R: Read>(reader: R) -> Result<ComicInfo, xml::reader::Error> {
+    let mut comic_info = ComicInfo::default();
+    let mut elements: Vec<String> = Vec::new();
+    let mut text = String::new();
+
+    for event in EventReader::new(reader) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                if name.local_name == "Page" {
+                    let mut page = ComicPageInfo::default();
+                    for attribute in attributes.iter() {
+                        let value = attribute.value.trim();
+                        match attribute.name.local_name.as_ref() {
+                            "Image" => page.image = value.parse().unwrap_or(0),
+                            "Type" => page.page_type = Some(value.to_string()),
+                            "DoublePage" => page.double_page = value.eq_ignore_ascii_case("true"),
+                            "ImageWidth" => page.width = value.parse().ok(),
+                            "ImageHeight" => page.height = value.parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    comic_info.pages.push(page);
+                }
+                elements.push(name.local_name);
+                text.clear();
+            }
+            XmlEvent::Characters(value) | XmlEvent::CData(value) => text.push_str(&value),
+            XmlEvent::EndElement { .. } => {
+                let element = elements.pop();
+                // Only direct children of the ComicInfo root hold the fields we care about.
+                if elements.len() == 1 {
+                    let value = Some(text.trim().to_string()).filter(|value| !value.is_empty());
+                    match element.as_ref().map(|element| element.as_str()) {
+                        Some("Title") => comic_info.title = value,
+                        Some("Series") => comic_info.series = value,
+                        Some("Number") => comic_info.number = value,
+                        Some("Writer") => comic_info.writer = value,
+                        Some("Publisher") => comic_info.publisher = value,
+                        Some("Summary") => comic_info.summary = value,
+                        // "Yes" or "YesAndRightToLeft"
+                        Some("Manga") => {
+                            comic_info.manga = value
+                                .map(|manga| manga.to_lowercase().starts_with("yes"))
+                                .unwrap_or(false)
+                        }
+                        Some("PageCount") => {
+                            comic_info.page_count = value.and_then(|count| count.parse().ok())
+                        }
+                        _ => {}
+                    }
+                }
+                text.clear();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(comic_info)
+}
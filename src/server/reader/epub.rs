@@ -1,10 +1,18 @@
 use actix_web::{fs::NamedFile, ResponseError};
-use config::Config;
 use epub::doc::EpubDoc;
-use models::Publication;
-use reader::models::Data;
-use std::{convert::From, error::Error, fmt, path::PathBuf};
+use fs::cache::Cache;
+use models::{Publication, COMIC, MAGAZINE, MANGA};
+use reader::models::{Data, Landmark, Metadata, Toc, TocEntry};
+use reader::opf::{self, Package};
+use reader::toc::{self, NavEntry};
+use std::{
+    convert::From,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
 use unzip;
+use xml::reader::{EventReader, XmlEvent};
 use zip::result::ZipError;
 
 #[derive(Debug)]
@@ -14,6 +22,7 @@ pub enum EpubError {
     PageNotFound,
     FileNotFound,
     ZipError(ZipError),
+    IOError(io::Error),
     GenericError(String),
 }
 
@@ -23,6 +32,12 @@ impl From<failure::Error> for EpubError {
     }
 }
 
+impl From<io::Error> for EpubError {
+    fn from(error: io::Error) -> Self {
+        EpubError::IOError(error)
+    }
+}
+
 impl fmt::Display for EpubError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -31,6 +46,7 @@ impl fmt::Display for EpubError {
             EpubError::PageNotFound => write!(f, "PageNotFound"),
             EpubError::FileNotFound => write!(f, "FileNotFound"),
             EpubError::ZipError(err) => write!(f, "ZipError: {:?}", err),
+            EpubError::IOError(err) => write!(f, "IOError: {:?}", err),
             EpubError::GenericError(err) => write!(f, "GenericError: {}", &err),
         }
     }
@@ -55,33 +71,447 @@ pub fn open(the_publication: &Publication) -> Result<Data, EpubError> {
     })
 }
 
+/// Table of contents and landmarks of the book. The EPUB 3 navigation document is preferred, the
+/// NCX and the guide of EPUB 2 are used when it is missing or empty.
+pub fn toc(the_publication: &Publication) -> Result<Toc, EpubError> {
+    let mut doc = EpubDoc::new(&the_publication.file)?;
+    let root_file = doc.root_file.clone();
+    let package = opf::parse(doc.get_resource_by_path(&root_file)?.as_slice())
+        .map_err(|err| EpubError::GenericError(err.to_string()))?;
+    let spine: Vec<PathBuf> = package
+        .spine
+        .iter()
+        .map(|id| {
+            package
+                .find_item(id)
+                .map(|item| opf::resolve_href(&root_file, &item.href))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let nav = package
+        .manifest
+        .iter()
+        .find(|item| item.has_property("nav"))
+        .map(|item| opf::resolve_href(&root_file, &item.href))
+        .and_then(|path| {
+            doc.get_resource_by_path(&path)
+                .ok()
+                .and_then(|content| toc::parse_nav(content.as_slice()).ok())
+                .map(|navigation| (path, navigation))
+        });
+
+    let mut the_toc = Toc::default();
+    if let Some((ref path, ref navigation)) = nav {
+        the_toc.entries = toc_entries(&spine, path, &navigation.toc);
+        the_toc.landmarks = navigation
+            .landmarks
+            .iter()
+            .map(|landmark| {
+                let (spine_index, fragment) = locate(&spine, path, &landmark.href);
+                Landmark {
+                    kind: landmark.kind.clone(),
+                    title: landmark.title.clone(),
+                    spine_index,
+                    fragment,
+                }
+            })
+            .collect();
+    }
+    if the_toc.entries.is_empty() {
+        if let Some((path, entries)) = ncx(&mut doc, &package, &root_file) {
+            the_toc.entries = toc_entries(&spine, &path, &entries);
+        }
+    }
+    if the_toc.landmarks.is_empty() {
+        the_toc.landmarks = package
+            .guide
+            .iter()
+            .map(|reference| {
+                let (spine_index, fragment) = locate(&spine, &root_file, &reference.href);
+                let kind = guide_kind(&reference.kind);
+                Landmark {
+                    title: reference.title.clone().unwrap_or_else(|| kind.clone()),
+                    kind,
+                    spine_index,
+                    fragment,
+                }
+            })
+            .collect();
+    }
+
+    Ok(the_toc)
+}
+
+fn ncx(doc: &mut EpubDoc, package: &Package, root_file: &Path) -> Option<(PathBuf, Vec<NavEntry>)> {
+    let item = package
+        .spine_toc
+        .as_ref()
+        .and_then(|id| package.find_item(id))
+        .or_else(|| {
+            package
+                .manifest
+                .iter()
+                .find(|item| item.media_type == "application/x-dtbncx+xml")
+        })?;
+    let path = opf::resolve_href(root_file, &item.href);
+    let content = doc.get_resource_by_path(&path).ok()?;
+    toc::parse_ncx(content.as_slice())
+        .ok()
+        .map(|entries| (path, entries))
+}
+
+fn toc_entries(spine: &[PathBuf], document: &Path, entries: &[NavEntry]) -> Vec<TocEntry> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (spine_index, fragment) = match entry.href {
+                Some(ref href) => locate(spine, document, href),
+                None => (None, None),
+            };
+            TocEntry {
+                title: entry.title.clone(),
+                spine_index,
+                fragment,
+                children: toc_entries(spine, document, &entry.children),
+            }
+        })
+        .collect()
+}
+
+/// Spine index and fragment an `href` found inside `document` points to.
+fn locate(spine: &[PathBuf], document: &Path, href: &str) -> (Option<usize>, Option<String>) {
+    let path = opf::resolve_href(document, href);
+    let fragment = href
+        .splitn(2, '#')
+        .nth(1)
+        .filter(|fragment| !fragment.is_empty())
+        .map(|fragment| fragment.to_string());
+    (spine.iter().position(|item| *item == path), fragment)
+}
+
+/// EPUB 2 guide types under the name EPUB 3 gives them in its landmarks.
+fn guide_kind(kind: &str) -> String {
+    match kind.to_lowercase().as_str() {
+        "text" | "start" => "bodymatter".to_string(),
+        "title-page" => "titlepage".to_string(),
+        kind => kind.to_string(),
+    }
+}
+
+pub fn metadata(file: &str) -> Result<Metadata, EpubError> {
+    let mut doc = EpubDoc::new(file)?;
+    let isbn = doc
+        .metadata
+        .get("identifier")
+        .and_then(|identifiers| identifiers.iter().filter_map(|id| parse_isbn(id)).next());
+
+    Ok(Metadata {
+        title: get_metadata(&doc, "title"),
+        author: get_metadata(&doc, "creator"),
+        isbn,
+        language: get_metadata(&doc, "language"),
+        media_type: media_type(&mut doc),
+        ..Metadata::default()
+    })
+}
+
+/// Media type named by `dc:type`, otherwise fixed layout books are taken to be comics since
+/// their pages are images rather than text that reflows.
+fn media_type(doc: &mut EpubDoc) -> Option<String> {
+    let dc_type = get_metadata(doc, "type")
+        .map(|dc_type| dc_type.to_lowercase())
+        .and_then(|dc_type| {
+            [MANGA, COMIC, MAGAZINE]
+                .iter()
+                .find(|name| dc_type.contains(*name))
+                .map(|name| name.to_string())
+                .or_else(|| match dc_type.contains("periodical") {
+                    true => Some(MAGAZINE.to_string()),
+                    false => None,
+                })
+        });
+    if dc_type.is_some() {
+        return dc_type;
+    }
+
+    let root_file = doc.root_file.clone();
+    let fixed_layout = doc
+        .get_resource_by_path(&root_file)
+        .ok()
+        .and_then(|content| opf::parse(content.as_slice()).ok())
+        .and_then(|package| {
+            package
+                .find_meta("rendition:layout")
+                .map(|layout| layout.content == "pre-paginated")
+        })
+        .unwrap_or(false);
+    match fixed_layout {
+        true => Some(COMIC.to_string()),
+        false => None,
+    }
+}
+
+fn get_metadata(doc: &EpubDoc, name: &str) -> Option<String> {
+    doc.mdata(name)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_isbn(identifier: &str) -> Option<String> {
+    let identifier = identifier.trim().to_lowercase();
+    let identifier = identifier
+        .trim_start_matches("urn:")
+        .trim_start_matches("isbn:")
+        .trim_start_matches("isbn");
+    let isbn: String = identifier
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+
+    let is_isbn_10 = isbn.len() == 10
+        && isbn.chars().take(9).all(|c| c.is_ascii_digit())
+        && isbn.chars().last().map(|c| c.is_ascii_digit() || c == 'X') == Some(true);
+    let is_isbn_13 = isbn.len() == 13 && isbn.chars().all(|c| c.is_ascii_digit());
+
+    match is_isbn_10 || is_isbn_13 {
+        true => Some(isbn),
+        false => None,
+    }
+}
+
+pub fn cover(file: &str, output_location: &str) -> Result<String, EpubError> {
+    let mut doc = EpubDoc::new(file)?;
+    let cover_path = find_cover(&mut doc).ok_or(EpubError::FileNotFound)?;
+    let content = doc.get_resource_by_path(&cover_path)?;
+    let file_name = cover_path.file_name().ok_or(EpubError::GenericError(
+        "Cover has no file name".to_string(),
+    ))?;
+
+    let mut output_path = PathBuf::from(output_location);
+    fs::create_dir_all(&output_path).map_err(EpubError::IOError)?;
+    output_path.push(file_name);
+    fs::write(&output_path, content).map_err(EpubError::IOError)?;
+
+    output_path
+        .to_str()
+        .map(|path| path.to_string())
+        .ok_or(EpubError::GenericError("Cover path is invalid".to_string()))
+}
+
+fn find_cover(doc: &mut EpubDoc) -> Option<PathBuf> {
+    // EPUB 2: <meta name="cover" content="{manifest id}"/>
+    let meta_cover = doc
+        .mdata("cover")
+        .and_then(|cover_id| doc.resources.get(&cover_id).cloned())
+        .filter(|(_, mime)| mime.starts_with("image/"))
+        .map(|(path, _)| path);
+    if meta_cover.is_some() {
+        return meta_cover;
+    }
+
+    // EPUB 3: <item properties="cover-image" .../>
+    let root_file = doc.root_file.clone();
+    let manifest_cover = doc
+        .get_resource_by_path(&root_file)
+        .ok()
+        .and_then(|content| opf::parse(content.as_slice()).ok())
+        .and_then(|package| {
+            package
+                .manifest
+                .into_iter()
+                .find(|item| item.has_property("cover-image") && item.is_image())
+        })
+        .map(|item| opf::resolve_href(&root_file, &item.href));
+    if manifest_cover.is_some() {
+        return manifest_cover;
+    }
+
+    // Fallback: the first image referenced by the spine.
+    for spine_id in doc.spine.clone().iter() {
+        let (path, mime) = match doc.resources.get(spine_id).cloned() {
+            Some(resource) => resource,
+            None => continue,
+        };
+        if mime.starts_with("image/") {
+            return Some(path);
+        }
+        let image = doc
+            .get_resource_by_path(&path)
+            .ok()
+            .and_then(|content| find_image_reference(content.as_slice()))
+            .map(|href| opf::resolve_href(&path, &href));
+        if image.is_some() {
+            return image;
+        }
+    }
+
+    None
+}
+
+fn find_image_reference(content: &[u8]) -> Option<String> {
+    for event in EventReader::new(content) {
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => {
+                let attribute_name = match name.local_name.as_ref() {
+                    "img" => "src",
+                    "image" => "href",
+                    _ => continue,
+                };
+                let reference = attributes
+                    .into_iter()
+                    .find(|attribute| attribute.name.local_name == attribute_name)
+                    .map(|attribute| attribute.value);
+                if reference.is_some() {
+                    return reference;
+                }
+            }
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+/// Plain text of every spine item, indexed by its page number.
+pub fn text(file: &str) -> Result<Vec<String>, EpubError> {
+    let mut doc = EpubDoc::new(file)?;
+    let mut pages = Vec::new();
+    for page_number in 0..doc.get_num_pages() {
+        doc.set_current_page(page_number)?;
+        let content = doc.get_current_str().unwrap_or_default();
+        pages.push(strip_html(&content));
+    }
+    Ok(pages)
+}
+
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        text.push(' ');
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => {
+                rest = "";
+                break;
+            }
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let tag_name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        let is_skipped = tag_name == "head" || tag_name == "script" || tag_name == "style";
+        if is_skipped && !tag.ends_with('/') {
+            let closing_tag = format!("</{}", tag_name);
+            rest = match find_ignore_case(rest, &closing_tag) {
+                Some(position) => &rest[position..],
+                None => "",
+            };
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity_end = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .map(|end| end + 1)
+            .filter(|end| rest[*end..].starts_with(';'));
+        match entity_end {
+            Some(end) => {
+                decoded.push_str(&decode_entity(&rest[1..end]));
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(entity: &str) -> String {
+    let decoded = match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+            u32::from_str_radix(&entity[2..], 16)
+                .ok()
+                .and_then(std::char::from_u32)
+        }
+        _ if entity.starts_with('#') => entity[1..]
+            .parse::<u32>()
+            .ok()
+            .and_then(std::char::from_u32),
+        _ => None,
+    };
+    match decoded {
+        Some(c) => c.to_string(),
+        None => format!("&{};", entity),
+    }
+}
+
 pub fn page(the_publication: &Publication, page_number: usize) -> Result<String, EpubError> {
     let mut doc = EpubDoc::new(&the_publication.file)?;
     doc.set_current_page(page_number)?;
     doc.get_current_str().map_err(|e| EpubError::EpubError(e))
 }
 
-const EXTRACT_LOCATION: &str = "cache";
-
+/// A resource of the book, e.g. a chapter or an image it refers to. `path` is relative to the root
+/// of the EPUB archive.
 pub fn file(
-    config: &Config,
+    cache: &Cache,
     the_publication: &Publication,
     path: PathBuf,
 ) -> Result<NamedFile, EpubError> {
-    let mut extract_location = PathBuf::from(config.pustaka_home.clone());
-    extract_location.push(EXTRACT_LOCATION);
-    extract_location.push(the_publication.id.to_string());
-
-    let mut filepath = PathBuf::from(extract_location.clone());
-    filepath.push(path);
-
-    if !filepath.exists() {
-        let extract_location_str: &str = extract_location.to_str().ok_or(
-            EpubError::GenericError("Unable to get extract location path".to_string()),
-        )?;
-        unzip::unzip(&the_publication.file, extract_location_str)
-            .map_err(|err| EpubError::ZipError(err))?;
-    }
+    let name = path
+        .iter()
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let filepath = cache.get_or_extract(the_publication.id, &path, |location| {
+        let location = location.to_str().ok_or(EpubError::GenericError(
+            "Unable to get extract location path".to_string(),
+        ))?;
+        unzip::unzip_by_name(&the_publication.file, location, &name)
+            .map(PathBuf::from)
+            .map_err(|err| match err {
+                ZipError::FileNotFound => EpubError::FileNotFound,
+                err => EpubError::ZipError(err),
+            })
+    })?;
 
     NamedFile::open(filepath).map_err(|_| EpubError::FileNotFound)
 }
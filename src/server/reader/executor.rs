@@ -0,0 +1,91 @@
+use actix::{prelude::*, Actor, SyncContext};
+use actix_web::Error;
+use fs::cache::Cache;
+use fs::page::PageTransform;
+use models::{Publication, CB7, CBR, CBT, CBZ, FOLDER, PDF};
+use reader::comic::{self, ComicError};
+use reader::models::PageInfo;
+use reader::pdf;
+use std::path::PathBuf;
+
+/// Reads through publications for the reader, which can take a while for large archives, on its
+/// own threads so it doesn't hold up the HTTP workers.
+pub struct ReaderExecutor;
+
+impl Actor for ReaderExecutor {
+    type Context = SyncContext<Self>;
+}
+
+/// Pages of a comic with their dimensions, see `comic::pages`.
+pub struct ListPages {
+    pub cache: Cache,
+    pub publication: Publication,
+}
+impl Message for ListPages {
+    type Result = Result<Vec<PageInfo>, ComicError>;
+}
+impl Handler<ListPages> for ReaderExecutor {
+    type Result = Result<Vec<PageInfo>, ComicError>;
+
+    fn handle(&mut self, msg: ListPages, _: &mut Self::Context) -> Self::Result {
+        comic::pages(&msg.cache, &msg.publication)
+    }
+}
+
+/// Path of a page of a comic or PDF, along with whether it could be transformed. Comic pages are
+/// resized and transcoded as asked for by `transform`.
+pub struct ReadPage {
+    pub cache: Cache,
+    pub publication: Publication,
+    pub page_number: usize,
+    pub transform: PageTransform,
+}
+impl Message for ReadPage {
+    type Result = Result<(PathBuf, bool), Error>;
+}
+impl Handler<ReadPage> for ReaderExecutor {
+    type Result = Result<(PathBuf, bool), Error>;
+
+    fn handle(&mut self, msg: ReadPage, _: &mut Self::Context) -> Self::Result {
+        let ReadPage {
+            cache,
+            publication,
+            page_number,
+            transform,
+        } = msg;
+        match publication.media_format.as_ref() {
+            CBR | CBZ | CB7 | CBT | FOLDER => {
+                read_page_comic(&cache, &publication, page_number, &transform)
+            }
+            PDF => pdf::page(&cache, &publication, page_number)
+                .map(|page| (page, true))
+                .map_err(|err| err.into()),
+            _ => Err(ComicError::InvalidMediaFormatError.into()),
+        }
+    }
+}
+
+fn read_page_comic(
+    cache: &Cache,
+    publication: &Publication,
+    page_number: usize,
+    transform: &PageTransform,
+) -> Result<(PathBuf, bool), Error> {
+    let page = comic::page(cache, &publication, page_number)?;
+    if transform.is_identity(&page) {
+        return Ok((page, true));
+    }
+
+    let derived_name = transform.derived_name(&page, page_number);
+    let derived = cache.get_or_extract(publication.id, &derived_name, |location| {
+        transform.apply(&page, &location.join(&derived_name))
+    });
+    match derived {
+        Ok(derived) => Ok((derived, true)),
+        // e.g. JPEG 2000, which the image crate can't decode, is still better than nothing
+        Err(err) => {
+            println!("Unable to transform {}: {}", page.to_string_lossy(), err);
+            Ok((page, false))
+        }
+    }
+}
@@ -1,3 +1,8 @@
 pub mod comic;
+pub mod comic_info;
 pub mod epub;
+pub mod executor;
 pub mod models;
+pub mod opf;
+pub mod pdf;
+pub mod toc;
@@ -16,3 +16,57 @@ pub struct Page {
     pub page_number: i32,
     pub url: Vec<String>,
 }
+
+/// A page of a comic as listed by the page manifest.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageInfo {
+    /// Index of the page, as used to read it.
+    pub number: usize,
+    /// Path of the image inside the archive.
+    pub name: String,
+    /// Missing when the image couldn't be decoded.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Wider than tall, usually two pages scanned as one.
+    pub spread: bool,
+}
+
+/// Where an entry of the table of contents leads to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TocEntry {
+    pub title: String,
+    /// Index of the document in the spine, as used to read the page. Missing when the entry
+    /// doesn't lead to a document of the spine.
+    pub spine_index: Option<usize>,
+    /// Element id inside the document, without the `#`.
+    pub fragment: Option<String>,
+    pub children: Vec<TocEntry>,
+}
+
+/// Structural point of an EPUB, named after the EPUB 3 landmarks, e.g. `cover` or `bodymatter`
+/// for the start of the text.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Landmark {
+    pub kind: String,
+    pub title: String,
+    pub spine_index: Option<usize>,
+    pub fragment: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Toc {
+    pub entries: Vec<TocEntry>,
+    pub landmarks: Vec<Landmark>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    pub language: Option<String>,
+    pub series: Option<String>,
+    pub issue: Option<String>,
+    /// Name of the media type the file itself claims to be, e.g. `models::COMIC`.
+    pub media_type: Option<String>,
+}
@@ -0,0 +1,178 @@
+use percent_encoding::percent_decode;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use xml::reader::{EventReader, XmlEvent};
+
+#[derive(Debug, Default, Clone)]
+pub struct Package {
+    pub manifest: Vec<ManifestItem>,
+    pub meta: Vec<Meta>,
+    /// Manifest ids of the documents in reading order.
+    pub spine: Vec<String>,
+    /// Manifest id of the EPUB 2 NCX.
+    pub spine_toc: Option<String>,
+    pub guide: Vec<Reference>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ManifestItem {
+    pub id: String,
+    pub href: String,
+    pub media_type: String,
+    pub properties: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Meta {
+    pub name: Option<String>,
+    pub property: Option<String>,
+    pub content: String,
+}
+
+/// EPUB 2 `<guide>` entry, e.g. the cover or where the text starts.
+#[derive(Debug, Default, Clone)]
+pub struct Reference {
+    pub kind: String,
+    pub title: Option<String>,
+    pub href: String,
+}
+
+impl ManifestItem {
+    pub fn has_property(&self, property: &str) -> bool {
+        self.properties.iter().any(|p| p == property)
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.media_type.starts_with("image/")
+    }
+}
+
+impl Package {
+    pub fn find_meta(&self, name: &str) -> Option<&Meta> {
+        self.meta.iter().find(|meta| {
+            meta.name.as_ref().map(|n| n.as_str()) == Some(name)
+                || meta.property.as_ref().map(|p| p.as_str()) == Some(name)
+        })
+    }
+
+    pub fn find_item(&self, id: &str) -> Option<&ManifestItem> {
+        self.manifest.iter().find(|item| item.id == id)
+    }
+}
+
+pub fn parse<R: Read>(reader: R) -> Result<Package, xml::reader::Error> {
+    let mut package = Package::default();
+    let mut current_meta: Option<Meta> = None;
+
+    for event in EventReader::new(reader) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                let attribute = |key: &str| {
+                    attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == key)
+                        .map(|attribute| attribute.value.clone())
+                };
+                match name.local_name.as_ref() {
+                    "item" => package.manifest.push(ManifestItem {
+                        id: attribute("id").unwrap_or_default(),
+                        href: attribute("href").unwrap_or_default(),
+                        media_type: attribute("media-type").unwrap_or_default(),
+                        properties: attribute("properties")
+                            .map(|properties| {
+                                properties
+                                    .split_whitespace()
+                                    .map(|property| property.to_string())
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    }),
+                    "spine" => package.spine_toc = attribute("toc"),
+                    "itemref" => {
+                        if let Some(idref) = attribute("idref") {
+                            package.spine.push(idref);
+                        }
+                    }
+                    "reference" => package.guide.push(Reference {
+                        kind: attribute("type").unwrap_or_default(),
+                        title: attribute("title"),
+                        href: attribute("href").unwrap_or_default(),
+                    }),
+                    "meta" => {
+                        current_meta = Some(Meta {
+                            name: attribute("name"),
+                            property: attribute("property"),
+                            content: attribute("content").unwrap_or_default(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::Characters(value) => {
+                if let Some(ref mut meta) = current_meta {
+                    meta.content.push_str(value.trim());
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                if name.local_name == "meta" {
+                    if let Some(meta) = current_meta.take() {
+                        package.meta.push(meta);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(package)
+}
+
+/// Resolve an `href` found inside `document` into a path relative to the root of the archive,
+/// dropping any fragment, decoding percent-escapes and collapsing `.` and `..` components.
+pub fn resolve_href(document: &Path, href: &str) -> PathBuf {
+    let href = href.split('#').next().unwrap_or("");
+    let href = percent_decode(href.as_bytes()).decode_utf8_lossy();
+    let base = document.parent().unwrap_or_else(|| Path::new(""));
+    let mut resolved = PathBuf::new();
+    for component in base.join(href.as_ref()).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other.as_os_str()),
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_href_is_relative_to_the_document() {
+        assert_eq!(
+            resolve_href(Path::new("OEBPS/content.opf"), "text/ch1.xhtml#start"),
+            PathBuf::from("OEBPS/text/ch1.xhtml")
+        );
+        assert_eq!(
+            resolve_href(Path::new("OEBPS/text/ch1.xhtml"), "../images/./cover.jpg"),
+            PathBuf::from("OEBPS/images/cover.jpg")
+        );
+    }
+
+    #[test]
+    fn resolve_href_decodes_percent_escapes() {
+        assert_eq!(
+            resolve_href(Path::new("content.opf"), "Cap%C3%ADtulo%201.xhtml"),
+            PathBuf::from("Capítulo 1.xhtml")
+        );
+        assert_eq!(
+            resolve_href(Path::new("content.opf"), "%28cover%29.jpg"),
+            PathBuf::from("(cover).jpg")
+        );
+    }
+}
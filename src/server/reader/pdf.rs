@@ -0,0 +1,177 @@
+use actix_web::ResponseError;
+use fs::cache::Cache;
+use lopdf::{Dictionary, Document, Object};
+use models::Publication;
+use reader::models::{Data, Metadata};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum PdfError {
+    PdfError(lopdf::Error),
+    PageNotFound,
+    ImageNotFound,
+    IOError(io::Error),
+    GenericError(String),
+}
+
+impl From<lopdf::Error> for PdfError {
+    fn from(error: lopdf::Error) -> Self {
+        PdfError::PdfError(error)
+    }
+}
+
+impl From<io::Error> for PdfError {
+    fn from(error: io::Error) -> Self {
+        PdfError::IOError(error)
+    }
+}
+
+impl fmt::Display for PdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PdfError::PdfError(err) => write!(f, "PdfError: {}", err),
+            PdfError::PageNotFound => write!(f, "PageNotFound"),
+            PdfError::ImageNotFound => write!(f, "ImageNotFound"),
+            PdfError::IOError(err) => write!(f, "IOError: {:?}", err),
+            PdfError::GenericError(err) => write!(f, "GenericError: {}", &err),
+        }
+    }
+}
+
+impl Error for PdfError {}
+
+impl ResponseError for PdfError {}
+
+pub fn open(the_publication: &Publication) -> Result<Data, PdfError> {
+    let doc = Document::load(&the_publication.file)?;
+    Ok(Data {
+        id: the_publication.id,
+        isbn: the_publication.isbn.clone(),
+        title: the_publication.title.clone(),
+        media_type_id: the_publication.media_type_id,
+        author_id: the_publication.author_id,
+        has_thumbnail: the_publication.has_thumbnail().clone(),
+        file: the_publication.file.clone(),
+        total_pages: doc.get_pages().len(),
+        media_format: the_publication.media_format.clone(),
+    })
+}
+
+pub fn metadata(file: &str) -> Result<Metadata, PdfError> {
+    let doc = Document::load(file)?;
+    let info = match doc.trailer.get(b"Info") {
+        Ok(info) => doc.dereference(info)?.1.as_dict()?,
+        Err(_) => return Ok(Metadata::default()),
+    };
+
+    Ok(Metadata {
+        title: get_info(&doc, info, b"Title"),
+        author: get_info(&doc, info, b"Author"),
+        ..Metadata::default()
+    })
+}
+
+fn get_info(doc: &Document, info: &Dictionary, key: &[u8]) -> Option<String> {
+    info.get(key)
+        .and_then(|value| doc.dereference(value))
+        .and_then(|(_, value)| value.as_str())
+        .ok()
+        .map(|value| decode_text_string(value).trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// PDF text strings are either UTF-16BE with a byte order mark or PDFDocEncoding, which matches
+/// Latin-1 for every printable character we care about.
+fn decode_text_string(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = bytes[2..]
+            .chunks(2)
+            .filter(|chunk| chunk.len() == 2)
+            .map(|chunk| ((chunk[0] as u16) << 8) | chunk[1] as u16)
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|byte| *byte as char).collect()
+    }
+}
+
+/// Split the requested page into its own single page PDF document so the reader doesn't need to
+/// download the whole file to show one page.
+pub fn page(
+    cache: &Cache,
+    the_publication: &Publication,
+    page_number: usize,
+) -> Result<PathBuf, PdfError> {
+    let page_name = format!("page-{}.pdf", page_number);
+    cache.get_or_extract(the_publication.id, Path::new(&page_name), |location| {
+        let mut doc = Document::load(&the_publication.file)?;
+        let pages = doc.get_pages();
+        let page_number = page_number as u32 + 1;
+        if !pages.contains_key(&page_number) {
+            return Err(PdfError::PageNotFound);
+        }
+
+        let other_pages: Vec<u32> = pages
+            .keys()
+            .filter(|number| **number != page_number)
+            .cloned()
+            .collect();
+        doc.delete_pages(&other_pages);
+        doc.prune_objects();
+
+        fs::create_dir_all(location).map_err(PdfError::IOError)?;
+        let page_path = location.join(&page_name);
+        doc.save(&page_path).map_err(PdfError::IOError)?;
+
+        Ok(page_path)
+    })
+}
+
+/// Pull the largest JPEG embedded in the first page. Most scanned PDFs store every page as a single
+/// JPEG image, which gives us a cover without having to render the page.
+pub fn cover(file: &str, output_location: &str) -> Result<String, PdfError> {
+    let doc = Document::load(file)?;
+    let first_page = doc
+        .get_pages()
+        .values()
+        .next()
+        .cloned()
+        .ok_or(PdfError::PageNotFound)?;
+
+    let (resource_dict, resource_ids) = doc.get_page_resources(first_page);
+    let mut resources: Vec<&Dictionary> = resource_dict.into_iter().collect();
+    for resource_id in resource_ids {
+        if let Ok(resource) = doc.get_dictionary(resource_id) {
+            resources.push(resource);
+        }
+    }
+
+    let image = resources
+        .iter()
+        .filter_map(|resource| resource.get(b"XObject").ok())
+        .filter_map(|xobjects| doc.dereference(xobjects).ok())
+        .filter_map(|(_, xobjects)| xobjects.as_dict().ok())
+        .flat_map(|xobjects| xobjects.iter())
+        .filter_map(|(_, xobject)| doc.dereference(xobject).ok())
+        .filter_map(|(_, xobject)| xobject.as_stream().ok())
+        .filter(|stream| {
+            stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(&b"Image"[..])
+                && stream.filter().ok() == Some("DCTDecode".to_string())
+        })
+        .max_by_key(|stream| stream.content.len())
+        .ok_or(PdfError::ImageNotFound)?;
+
+    let mut output_path = PathBuf::from(output_location);
+    fs::create_dir_all(&output_path).map_err(PdfError::IOError)?;
+    output_path.push("cover.jpg");
+    fs::write(&output_path, &image.content).map_err(PdfError::IOError)?;
+
+    output_path
+        .to_str()
+        .map(|path| path.to_string())
+        .ok_or(PdfError::GenericError("Cover path is invalid".to_string()))
+}
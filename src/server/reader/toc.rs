@@ -0,0 +1,311 @@
+use std::io::Read;
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent};
+
+/// Table of contents entry as written in the book, `href` is relative to the navigation document.
+#[derive(Debug, Default, Clone)]
+pub struct NavEntry {
+    pub title: String,
+    /// Missing for headings that only group other entries.
+    pub href: Option<String>,
+    pub children: Vec<NavEntry>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct NavLandmark {
+    pub kind: String,
+    pub title: String,
+    pub href: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Navigation {
+    pub toc: Vec<NavEntry>,
+    pub landmarks: Vec<NavLandmark>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Toc,
+    Landmarks,
+    Other,
+}
+
+/// Parse the `toc` and `landmarks` lists of an EPUB 3 navigation document.
+pub fn parse_nav<R: Read>(reader: R) -> Result<Navigation, xml::reader::Error> {
+    let mut navigation = Navigation::default();
+    let mut section = Section::Other;
+    let mut entries: Vec<NavEntry> = Vec::new();
+    let mut landmark: Option<NavLandmark> = None;
+    // depth inside the element holding the label of the current entry
+    let mut label_depth = 0;
+    let mut label = String::new();
+
+    for event in EventReader::new(reader) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                if label_depth > 0 {
+                    label_depth += 1;
+                    continue;
+                }
+                match (section, name.local_name.as_ref()) {
+                    (_, "nav") => {
+                        section = match epub_type(&attributes).as_ref().map(|t| t.as_str()) {
+                            Some("toc") => Section::Toc,
+                            Some("landmarks") => Section::Landmarks,
+                            _ => Section::Other,
+                        }
+                    }
+                    (Section::Toc, "li") => entries.push(NavEntry::default()),
+                    (Section::Toc, "a") | (Section::Toc, "span") => {
+                        if let Some(entry) = entries.last_mut() {
+                            entry.href = attribute(&attributes, "href");
+                        }
+                        label_depth = 1;
+                    }
+                    (Section::Landmarks, "a") => {
+                        landmark = Some(NavLandmark {
+                            kind: epub_type(&attributes).unwrap_or_default(),
+                            title: String::new(),
+                            href: attribute(&attributes, "href").unwrap_or_default(),
+                        });
+                        label_depth = 1;
+                    }
+                    _ => {}
+                }
+                label.clear();
+            }
+            XmlEvent::Characters(value) | XmlEvent::CData(value) | XmlEvent::Whitespace(value) => {
+                if label_depth > 0 {
+                    label.push_str(&value);
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                if label_depth > 0 {
+                    label_depth -= 1;
+                    if label_depth > 0 {
+                        continue;
+                    }
+                    let title = normalize(&label);
+                    match section {
+                        Section::Toc => {
+                            if let Some(entry) = entries.last_mut() {
+                                entry.title = title;
+                            }
+                        }
+                        Section::Landmarks => {
+                            if let Some(mut landmark) = landmark.take() {
+                                landmark.title = title;
+                                navigation.landmarks.push(landmark);
+                            }
+                        }
+                        Section::Other => {}
+                    }
+                    continue;
+                }
+                match (section, name.local_name.as_ref()) {
+                    (_, "nav") => section = Section::Other,
+                    (Section::Toc, "li") => {
+                        if let Some(entry) = entries.pop() {
+                            match entries.last_mut() {
+                                Some(parent) => parent.children.push(entry),
+                                None => navigation.toc.push(entry),
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(navigation)
+}
+
+/// Parse the `navMap` of an EPUB 2 NCX.
+pub fn parse_ncx<R: Read>(reader: R) -> Result<Vec<NavEntry>, xml::reader::Error> {
+    let mut toc = Vec::new();
+    let mut entries: Vec<NavEntry> = Vec::new();
+    let mut in_text = false;
+    let mut label = String::new();
+
+    for event in EventReader::new(reader) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match name.local_name.as_ref() {
+                "navPoint" => entries.push(NavEntry::default()),
+                "text" if !entries.is_empty() => {
+                    in_text = true;
+                    label.clear();
+                }
+                "content" => {
+                    if let Some(entry) = entries.last_mut() {
+                        entry.href = attribute(&attributes, "src");
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::Characters(value) | XmlEvent::CData(value) => {
+                if in_text {
+                    label.push_str(&value);
+                }
+            }
+            XmlEvent::EndElement { name } => match name.local_name.as_ref() {
+                "text" if in_text => {
+                    in_text = false;
+                    // the label of a navPoint comes before the navPoints nested in it
+                    if let Some(entry) = entries.last_mut() {
+                        if entry.title.is_empty() {
+                            entry.title = normalize(&label);
+                        }
+                    }
+                }
+                "navPoint" => {
+                    if let Some(entry) = entries.pop() {
+                        match entries.last_mut() {
+                            Some(parent) => parent.children.push(entry),
+                            None => toc.push(entry),
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(toc)
+}
+
+fn attribute(attributes: &[OwnedAttribute], key: &str) -> Option<String> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name.local_name == key && attribute.name.prefix.is_none())
+        .map(|attribute| attribute.value.clone())
+}
+
+fn epub_type(attributes: &[OwnedAttribute]) -> Option<String> {
+    attributes
+        .iter()
+        .find(|attribute| {
+            attribute.name.local_name == "type"
+                && attribute.name.prefix.as_ref().map(|prefix| prefix.as_str()) == Some("epub")
+        })
+        .map(|attribute| attribute.value.trim().to_string())
+}
+
+fn normalize(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAV: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+  <body>
+    <nav epub:type="toc">
+      <h1>Contents</h1>
+      <ol>
+        <li><a href="cover.xhtml">Cover</a></li>
+        <li>
+          <span>Part <b>One</b></span>
+          <ol>
+            <li><a href="chapter1.xhtml">Chapter
+              <em>1</em>:   The <span>Start</span></a></li>
+            <li><a href="chapter2.xhtml#middle">Chapter 2</a></li>
+          </ol>
+        </li>
+      </ol>
+    </nav>
+    <nav epub:type="landmarks">
+      <ol>
+        <li><a epub:type="cover" href="cover.xhtml">Cover</a></li>
+        <li><a epub:type="bodymatter" href="chapter1.xhtml">Start <i>reading</i></a></li>
+      </ol>
+    </nav>
+  </body>
+</html>"#;
+
+    const NCX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <navMap>
+    <navPoint id="p1" playOrder="1">
+      <navLabel><text>Part  One</text></navLabel>
+      <content src="part1.xhtml"/>
+      <navPoint id="c1" playOrder="2">
+        <navLabel><text>Chapter 1</text></navLabel>
+        <content src="chapter1.xhtml"/>
+      </navPoint>
+      <navPoint id="c2" playOrder="3">
+        <navLabel><text>Chapter 2</text></navLabel>
+        <content src="chapter2.xhtml#middle"/>
+      </navPoint>
+    </navPoint>
+    <navPoint id="p2" playOrder="4">
+      <navLabel><text>Part Two</text></navLabel>
+      <content src="part2.xhtml"/>
+    </navPoint>
+  </navMap>
+</ncx>"#;
+
+    #[test]
+    fn parse_nav_nests_entries_under_headings() {
+        let toc = parse_nav(NAV.as_bytes()).unwrap().toc;
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].title, "Cover");
+        assert_eq!(toc[0].href, Some("cover.xhtml".to_string()));
+        assert!(toc[0].children.is_empty());
+
+        let part = &toc[1];
+        assert_eq!(part.title, "Part One");
+        assert_eq!(part.href, None);
+        assert_eq!(part.children.len(), 2);
+        assert_eq!(part.children[1].title, "Chapter 2");
+        assert_eq!(
+            part.children[1].href,
+            Some("chapter2.xhtml#middle".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_nav_joins_labels_split_across_elements() {
+        let toc = parse_nav(NAV.as_bytes()).unwrap().toc;
+        let chapter = &toc[1].children[0];
+        assert_eq!(chapter.title, "Chapter 1: The Start");
+        assert_eq!(chapter.href, Some("chapter1.xhtml".to_string()));
+    }
+
+    #[test]
+    fn parse_nav_reads_landmarks() {
+        let landmarks = parse_nav(NAV.as_bytes()).unwrap().landmarks;
+        assert_eq!(landmarks.len(), 2);
+        assert_eq!(landmarks[0].kind, "cover");
+        assert_eq!(landmarks[0].title, "Cover");
+        assert_eq!(landmarks[0].href, "cover.xhtml");
+        assert_eq!(landmarks[1].kind, "bodymatter");
+        assert_eq!(landmarks[1].title, "Start reading");
+        assert_eq!(landmarks[1].href, "chapter1.xhtml");
+    }
+
+    #[test]
+    fn parse_ncx_nests_nav_points() {
+        let toc = parse_ncx(NCX.as_bytes()).unwrap();
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].title, "Part One");
+        assert_eq!(toc[0].href, Some("part1.xhtml".to_string()));
+        assert_eq!(toc[0].children.len(), 2);
+        assert_eq!(toc[0].children[0].title, "Chapter 1");
+        assert_eq!(
+            toc[0].children[1].href,
+            Some("chapter2.xhtml#middle".to_string())
+        );
+        assert_eq!(toc[1].title, "Part Two");
+        assert!(toc[1].children.is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+use actix::prelude::*;
+use models::{Publication, EPUB};
+use reader::epub;
+use scan::actor::{catch_panic, Scanner};
+use scan::error::ScannerError;
+
+pub struct ExtractContent {
+    pub publication: Publication,
+}
+
+impl Message for ExtractContent {
+    type Result = Result<(Publication, Vec<String>), ScannerError>;
+}
+
+impl Handler<ExtractContent> for Scanner {
+    type Result = Result<(Publication, Vec<String>), ScannerError>;
+
+    fn handle(&mut self, msg: ExtractContent, _: &mut Self::Context) -> Self::Result {
+        let publication = msg.publication;
+        catch_panic(|| match publication.media_format.as_ref() {
+            EPUB => epub::text(&publication.file)
+                .map(|pages| (publication, pages))
+                .map_err(|_| ScannerError::ContentError),
+            _ => Ok((publication, vec![])),
+        })
+    }
+}
@@ -1,60 +1,155 @@
 use actix::prelude::*;
 use config::Config;
 use fs::thumbnail;
-use models::{CategoryId, Publication, CBR, CBZ, EPUB};
-use reader::comic;
-use scan::actor::Scanner;
+use models::{CategoryId, Publication, CB7, CBR, CBT, CBZ, EPUB, FOLDER, PDF};
+use reader::{comic, epub, models::Metadata, pdf};
+use scan::actor::{catch_panic, Scanner};
 use scan::error::ScannerError;
+use scan::media_type::MediaTypeDetection;
 
 pub struct LoadMetadata {
     pub config: Config,
+    pub detection: MediaTypeDetection,
     pub publication: Publication,
     pub category_id: CategoryId,
 }
 
 impl Message for LoadMetadata {
-    type Result = Result<(Publication, CategoryId), ScannerError>;
+    type Result = Result<(Publication, CategoryId, Metadata), ScannerError>;
 }
 
 impl Handler<LoadMetadata> for Scanner {
-    type Result = Result<(Publication, CategoryId), ScannerError>;
+    type Result = Result<(Publication, CategoryId, Metadata), ScannerError>;
 
     fn handle(&mut self, msg: LoadMetadata, _: &mut Self::Context) -> Self::Result {
-        let publication = msg.publication;
-        let thumbnail = get_thumbnail(&msg.config, &publication);
-        let updated_publication = Publication {
-            thumbnail,
-            ..publication
-        };
-        Ok((updated_publication, msg.category_id))
+        let LoadMetadata {
+            config,
+            detection,
+            publication,
+            category_id,
+        } = msg;
+        let (publication, metadata) = catch_panic(|| Ok(load(&config, &detection, publication)))?;
+        Ok((publication, category_id, metadata))
+    }
+}
+
+/// Reload the metadata and thumbnail of a publication that is already categorized, e.g. because
+/// its file changed since the last scan.
+pub struct RefreshMetadata {
+    pub config: Config,
+    pub detection: MediaTypeDetection,
+    pub publication: Publication,
+}
+
+impl Message for RefreshMetadata {
+    type Result = Result<(Publication, Metadata), ScannerError>;
+}
+
+impl Handler<RefreshMetadata> for Scanner {
+    type Result = Result<(Publication, Metadata), ScannerError>;
+
+    fn handle(&mut self, msg: RefreshMetadata, _: &mut Self::Context) -> Self::Result {
+        catch_panic(|| Ok(load(&msg.config, &msg.detection, msg.publication)))
+    }
+}
+
+fn load(
+    config: &Config,
+    detection: &MediaTypeDetection,
+    publication: Publication,
+) -> (Publication, Metadata) {
+    let metadata = get_metadata(&publication);
+    let media_type_id = detection.detect(&publication, &metadata);
+    let thumbnail = get_thumbnail(config, &publication);
+    let thumbnail_hash = thumbnail
+        .as_ref()
+        .and_then(|thumbnail| hash_thumbnail(thumbnail));
+    let updated_publication = Publication {
+        title: metadata.title.clone().unwrap_or(publication.title.clone()),
+        isbn: metadata.isbn.clone().unwrap_or(publication.isbn.clone()),
+        language: metadata.language.clone().or(publication.language.clone()),
+        series: metadata.series.clone().or(publication.series.clone()),
+        issue: metadata.issue.clone().or(publication.issue.clone()),
+        media_type_id,
+        thumbnail,
+        thumbnail_hash,
+        ..publication
+    };
+    (updated_publication, metadata)
+}
+
+pub fn hash_thumbnail(thumbnail: &str) -> Option<i64> {
+    thumbnail::perceptual_hash(thumbnail)
+        .map_err(|err| eprintln!("Unable to hash thumbnail {}: {}", thumbnail, err))
+        .ok()
+}
+
+pub fn get_metadata(publication: &Publication) -> Metadata {
+    match publication.media_format.as_ref() {
+        CBR | CBZ | CB7 | CBT | FOLDER => comic::comic_info(publication)
+            .map(|comic_info| {
+                comic_info
+                    .map(|comic_info| comic_info.to_metadata())
+                    .unwrap_or_default()
+            })
+            .unwrap_or_else(|err| {
+                println!("Unable to read metadata of {}: {}", publication.file, err);
+                Metadata::default()
+            }),
+        EPUB => epub::metadata(&publication.file).unwrap_or_else(|err| {
+            println!("Unable to read metadata of {}: {}", publication.file, err);
+            Metadata::default()
+        }),
+        PDF => pdf::metadata(&publication.file).unwrap_or_else(|err| {
+            println!("Unable to read metadata of {}: {}", publication.file, err);
+            Metadata::default()
+        }),
+        _ => Metadata::default(),
     }
 }
 
 fn get_thumbnail(config: &Config, publication: &Publication) -> Option<String> {
     match publication.media_format.as_ref() {
-        CBR => get_thumbnail_cbr(config, publication),
-        CBZ => get_thumbnail_cbz(config, publication),
-        EPUB => None,
+        CBR | CBZ | CB7 | CBT | FOLDER => get_thumbnail_comic(config, publication),
+        EPUB => get_thumbnail_epub(config, publication),
+        PDF => get_thumbnail_pdf(config, publication),
         _ => None,
     }
 }
 
-fn get_thumbnail_cbr(config: &Config, publication: &Publication) -> Option<String> {
+fn get_thumbnail_epub(config: &Config, publication: &Publication) -> Option<String> {
+    let thumbnail_location =
+        thumbnail::generate_thumbnail_location(&config.pustaka_home, publication.id);
+    let thumbnail_location = thumbnail_location.to_str()?;
+    let thumbnail_path = epub::cover(&publication.file, thumbnail_location).ok();
+    match thumbnail_path {
+        Some(thumbnail_path) => thumbnail::resize(&thumbnail_path).ok(),
+        None => None,
+    }
+}
+
+fn get_thumbnail_comic(config: &Config, publication: &Publication) -> Option<String> {
     let thumbnail_location =
         thumbnail::generate_thumbnail_location(&config.pustaka_home, publication.id);
     let thumbnail_location = thumbnail_location.to_str()?;
-    let thumbnail_path = comic::page_cbr(&publication.file, 0, thumbnail_location).ok();
+    let thumbnail_path = comic::extract_page(
+        &publication.file,
+        &publication.media_format,
+        0,
+        thumbnail_location,
+    )
+    .ok();
     match thumbnail_path {
         Some(thumbnail_path) => thumbnail::resize(&thumbnail_path).ok(),
         None => None,
     }
 }
 
-fn get_thumbnail_cbz(config: &Config, publication: &Publication) -> Option<String> {
+fn get_thumbnail_pdf(config: &Config, publication: &Publication) -> Option<String> {
     let thumbnail_location =
         thumbnail::generate_thumbnail_location(&config.pustaka_home, publication.id);
     let thumbnail_location = thumbnail_location.to_str()?;
-    let thumbnail_path = comic::page_cbz(&publication.file, 0, thumbnail_location).ok();
+    let thumbnail_path = pdf::cover(&publication.file, thumbnail_location).ok();
     match thumbnail_path {
         Some(thumbnail_path) => thumbnail::resize(&thumbnail_path).ok(),
         None => None,
@@ -1,9 +1,17 @@
 use actix::prelude::*;
+use chrono::NaiveDateTime;
 use models;
+use scan::error::ScannerError;
+use std::fs::Metadata;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::UNIX_EPOCH;
 use walkdir::DirEntry;
 
+pub mod extract_content;
 pub mod load_metadata;
+pub mod preview_file;
 pub mod process_file;
+pub mod reconcile;
 pub mod scan_folder;
 
 #[derive(Debug, Clone, Copy)]
@@ -12,6 +20,23 @@ pub struct Scanner;
 impl Actor for Scanner {
     type Context = SyncContext<Self>;
 }
+
+/// Run `f`, turning a panic into an error. The readers lean on third party parsers that can panic
+/// on a malformed file, which would otherwise take the scanner thread down with it.
+pub fn catch_panic<T, F>(f: F) -> Result<T, ScannerError>
+where
+    F: FnOnce() -> Result<T, ScannerError>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|err| {
+        let message = err
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| err.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(ScannerError::Panic(message))
+    })
+}
+
 pub type CategoryId = i32;
 #[derive(Debug, Clone)]
 pub struct Category {
@@ -32,13 +57,19 @@ pub type FileId = i32;
 
 #[derive(Debug, Clone)]
 pub struct File {
+    pub library_id: models::LibraryId,
     pub name: String,
     pub path: String,
     pub extension: String,
+    pub size: i64,
+    pub modified: Option<NaiveDateTime>,
+    pub hash: Option<String>,
 }
 impl File {
-    pub fn from(dir: &DirEntry) -> Self {
+    pub fn from(library_id: models::LibraryId, dir: &DirEntry) -> Self {
+        let metadata = dir.metadata().ok();
         Self {
+            library_id,
             name: dir.file_name().to_str().unwrap_or("").to_string(),
             path: dir.path().to_str().unwrap_or("").to_string(),
             extension: dir
@@ -47,6 +78,21 @@ impl File {
                 .and_then(|s| s.to_str())
                 .unwrap_or("")
                 .to_string(),
+            size: metadata
+                .as_ref()
+                .map(|metadata| metadata.len() as i64)
+                .unwrap_or(0),
+            modified: metadata.as_ref().and_then(modified_time),
+            hash: None,
         }
     }
 }
+
+/// Whole seconds only, so the value survives the round trip through the database.
+pub fn modified_time(metadata: &Metadata) -> Option<NaiveDateTime> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .and_then(|duration| NaiveDateTime::from_timestamp_opt(duration.as_secs() as i64, 0))
+}
@@ -0,0 +1,44 @@
+use actix::prelude::*;
+use models::Publication;
+use reader::models::Metadata;
+use scan::actor::load_metadata::get_metadata;
+use scan::actor::process_file::{categorize, Categorized, ProcessFile};
+use scan::actor::{catch_panic, Scanner};
+use scan::error::ScannerError;
+
+/// Work out what `ProcessFile` and `LoadMetadata` would do with a file without saving anything.
+#[derive(Debug, Clone)]
+pub struct PreviewFile(pub ProcessFile);
+impl Message for PreviewFile {
+    type Result = Result<(Option<Categorized>, Metadata), ScannerError>;
+}
+
+impl Handler<PreviewFile> for Scanner {
+    type Result = Result<(Option<Categorized>, Metadata), ScannerError>;
+
+    fn handle(&mut self, msg: PreviewFile, _: &mut Self::Context) -> Self::Result {
+        let process = msg.0;
+        let categorized = categorize(&process);
+        let file = &process.file;
+        let publication = Publication {
+            id: 0,
+            isbn: "".to_string(),
+            title: file.name.clone(),
+            media_type_id: 1,
+            media_format: file.extension.clone(),
+            author_id: 1,
+            thumbnail: None,
+            file: file.path.clone(),
+            timestamp: None,
+            language: None,
+            series: None,
+            issue: None,
+            file_size: Some(file.size),
+            file_modified: file.modified,
+            file_hash: file.hash.clone(),
+            missing: false,
+            thumbnail_hash: None,
+        };
+        catch_panic(|| Ok((categorized, get_metadata(&publication))))
+    }
+}
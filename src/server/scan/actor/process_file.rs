@@ -1,7 +1,10 @@
 use actix::prelude::*;
 use config::Config;
+use models::UNCATEGORIZED;
 use scan::actor::{Category, CategoryId, File, Scanner};
 use scan::error::ScannerError;
+use scan::rule::Rule;
+use std::cmp::Ordering;
 use std::path::Path;
 
 #[derive(Debug, Clone)]
@@ -9,6 +12,12 @@ pub struct ProcessFile {
     pub config: Config,
     pub publication_path: String,
     pub categories: Vec<Category>,
+    /// Checked in order before trying to match the category names.
+    pub rules: Vec<Rule>,
+    /// Lowest score a category name needs to match the file path.
+    pub min_score: f64,
+    /// Used when neither the rules nor the category names match the file.
+    pub fallback_category_id: Option<CategoryId>,
     pub file: File,
 }
 impl Message for ProcessFile {
@@ -19,29 +28,78 @@ impl Handler<ProcessFile> for Scanner {
     type Result = Result<(File, CategoryId), ScannerError>;
 
     fn handle(&mut self, msg: ProcessFile, _: &mut Self::Context) -> Self::Result {
-        let file = msg.file;
-        let categories = msg.categories;
-        let matched_category = process_category(&msg.publication_path, &file, &categories)?;
-        Ok((file, matched_category.id))
+        let categorized = categorize(&msg).ok_or(ScannerError::NoMatchCategory)?;
+        Ok((msg.file, categorized.category_id))
     }
 }
 
-fn process_category<'a>(
-    publication_path: &str,
-    file: &File,
-    categories: &'a [Category],
-) -> Result<&'a Category, ScannerError> {
-    match categories.len() == 0 {
-        true => Err(ScannerError::EmptyCategoryError),
-        false => {
-            let matched_category = categories
-                .iter()
-                .map(|category| (rank_category(publication_path, category, file), category))
-                .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
-                .map(|(_, cat)| cat);
-            matched_category.ok_or(ScannerError::NoMatchCategory)
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    Rule,
+    Name,
+    Fallback,
+}
+
+/// The category picked for a file and how it was picked. The scores are only there when the
+/// category names were matched against the file path.
+#[derive(Debug, Clone)]
+pub struct Categorized {
+    pub category_id: CategoryId,
+    pub matched_by: MatchedBy,
+    pub score: Option<f64>,
+    pub runner_up: Option<(CategoryId, f64)>,
+}
+
+pub fn categorize(msg: &ProcessFile) -> Option<Categorized> {
+    let relative_path = relative_path(&msg.publication_path, &msg.file);
+    if let Some(rule) = msg.rules.iter().find(|rule| rule.matches(&relative_path)) {
+        return Some(Categorized {
+            category_id: rule.category_id,
+            matched_by: MatchedBy::Rule,
+            score: None,
+            runner_up: None,
+        });
     }
+
+    // uncategorized is only ever the fallback, it would otherwise win for paths that happen to
+    // contain its name or push a real category out of the runner up spot
+    let mut ranked: Vec<(f64, &Category)> = msg
+        .categories
+        .iter()
+        .filter(|category| category.name != UNCATEGORIZED)
+        .map(|category| {
+            (
+                rank_category(&msg.publication_path, category, &msg.file),
+                category,
+            )
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+    let runner_up = ranked.get(1).map(|(score, category)| (category.id, *score));
+
+    match ranked.first() {
+        Some((score, category)) if *score >= msg.min_score => Some(Categorized {
+            category_id: category.id,
+            matched_by: MatchedBy::Name,
+            score: Some(*score),
+            runner_up,
+        }),
+        best => msg.fallback_category_id.map(|category_id| Categorized {
+            category_id,
+            matched_by: MatchedBy::Fallback,
+            score: best.map(|(score, _)| *score),
+            runner_up: best.map(|(score, category)| (category.id, *score)),
+        }),
+    }
+}
+
+fn relative_path(publication_path: &str, file: &File) -> String {
+    let path = Path::new(&file.path);
+    path.strip_prefix(publication_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace("\\", "/")
 }
 
 fn rank_category(publication_path: &str, category: &Category, file: &File) -> f64 {
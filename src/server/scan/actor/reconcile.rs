@@ -0,0 +1,145 @@
+use actix::prelude::*;
+use fs::hash;
+use models::Publication;
+use scan::actor::load_metadata::hash_thumbnail;
+use scan::actor::{File, Scanner};
+use scan::error::ScannerError;
+use std::collections::{HashMap, HashSet};
+
+/// Compare the files found by `ScanFolder` against the publications already in the database.
+#[derive(Debug, Clone)]
+pub struct Reconcile {
+    pub files: Vec<File>,
+    pub publications: Vec<Publication>,
+    /// Hashes of files that were merged into another publication.
+    pub ignored: HashSet<String>,
+    /// Treat every known file as changed, so that its metadata and thumbnail are reloaded.
+    pub refresh: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    /// Files that aren't in the database yet, with their hash filled in.
+    pub new_files: Vec<File>,
+    /// Publications whose file was found at a different path, with the path rewritten.
+    pub moved: Vec<Publication>,
+    /// Publications whose file changed since the last scan and need their metadata reloaded.
+    pub changed: Vec<Publication>,
+    /// Publications whose file is unchanged but didn't have its size, time, hash or thumbnail hash
+    /// recorded.
+    pub updated: Vec<Publication>,
+    /// Publications whose file is gone.
+    pub missing: Vec<Publication>,
+    /// Files that aren't in the database because they were merged into another publication.
+    pub ignored: Vec<File>,
+}
+
+impl Message for Reconcile {
+    type Result = Result<Reconciliation, ScannerError>;
+}
+
+impl Handler<Reconcile> for Scanner {
+    type Result = Result<Reconciliation, ScannerError>;
+
+    fn handle(&mut self, msg: Reconcile, _: &mut Self::Context) -> Self::Result {
+        let mut reconciliation = Reconciliation::default();
+        let found_paths: HashSet<String> = msg.files.iter().map(|file| file.path.clone()).collect();
+        let (known, mut gone): (Vec<Publication>, Vec<Publication>) = msg
+            .publications
+            .into_iter()
+            .partition(|publication| found_paths.contains(&publication.file));
+        let known: HashMap<String, Publication> = known
+            .into_iter()
+            .map(|publication| (publication.file.clone(), publication))
+            .collect();
+
+        for file in msg.files.into_iter() {
+            match known.get(&file.path) {
+                Some(publication) => {
+                    let modified = publication.missing
+                        || publication.file_size != Some(file.size)
+                        || publication.file_modified != file.modified;
+                    if modified {
+                        reconciliation.changed.push(with_file(
+                            publication.clone(),
+                            &file,
+                            hash_file(&file),
+                        ));
+                    } else if msg.refresh {
+                        let hash = publication.file_hash.clone().or_else(|| hash_file(&file));
+                        reconciliation
+                            .changed
+                            .push(with_file(publication.clone(), &file, hash));
+                    } else if publication.file_hash.is_none()
+                        || (publication.has_thumbnail() && publication.thumbnail_hash.is_none())
+                    {
+                        let hash = publication.file_hash.clone().or_else(|| hash_file(&file));
+                        reconciliation
+                            .updated
+                            .push(with_file(publication.clone(), &file, hash));
+                    }
+                }
+                None => {
+                    let file = File {
+                        hash: hash_file(&file),
+                        ..file
+                    };
+                    let moved_from = gone.iter().position(|publication| {
+                        file.hash.is_some()
+                            && publication.file_size == Some(file.size)
+                            && publication.file_hash == file.hash
+                    });
+                    match moved_from {
+                        Some(index) => {
+                            let publication = gone.remove(index);
+                            let hash = file.hash.clone();
+                            reconciliation
+                                .moved
+                                .push(with_file(publication, &file, hash));
+                        }
+                        None if is_ignored(&file, &msg.ignored) => {
+                            reconciliation.ignored.push(file)
+                        }
+                        None => reconciliation.new_files.push(file),
+                    }
+                }
+            }
+        }
+
+        reconciliation.missing = gone
+            .into_iter()
+            .filter(|publication| !publication.missing)
+            .collect();
+        Ok(reconciliation)
+    }
+}
+
+fn hash_file(file: &File) -> Option<String> {
+    hash::sha256(&file.path)
+        .map_err(|err| eprintln!("Unable to hash {}: {}", file.path, err))
+        .ok()
+}
+
+fn is_ignored(file: &File, ignored: &HashSet<String>) -> bool {
+    file.hash
+        .as_ref()
+        .map(|hash| ignored.contains(hash))
+        .unwrap_or(false)
+}
+
+fn with_file(publication: Publication, file: &File, hash: Option<String>) -> Publication {
+    let thumbnail_hash = match (&publication.thumbnail, publication.thumbnail_hash) {
+        (_, Some(thumbnail_hash)) => Some(thumbnail_hash),
+        (Some(ref thumbnail), None) if publication.has_thumbnail() => hash_thumbnail(thumbnail),
+        _ => None,
+    };
+    Publication {
+        file: file.path.clone(),
+        thumbnail_hash,
+        file_size: Some(file.size),
+        file_modified: file.modified,
+        file_hash: hash,
+        missing: false,
+        ..publication
+    }
+}
@@ -1,22 +1,29 @@
 use actix::prelude::*;
-use scan::actor::{File, Scanner};
+use models::{LibraryId, FOLDER};
+use reader::{comic, comic_info};
+use scan::actor::{modified_time, File, Scanner};
 use scan::error::ScannerError;
 use std::collections::HashSet;
+use std::fs;
 use walkdir::DirEntry;
 use walkdir::WalkDir;
 
 lazy_static! {
-    static ref ACCEPTED_EXTENSIONS: HashSet<&'static str> = {
+    pub static ref ACCEPTED_EXTENSIONS: HashSet<&'static str> = {
         let mut m = HashSet::new();
+        m.insert("cb7");
         m.insert("cbr");
+        m.insert("cbt");
         m.insert("cbz");
         m.insert("epub");
+        m.insert("pdf");
         m
     };
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanFolder {
+    pub library_id: LibraryId,
     pub publication_path: String,
 }
 impl Message for ScanFolder {
@@ -27,14 +34,65 @@ impl Handler<ScanFolder> for Scanner {
     type Result = Result<Vec<File>, ScannerError>;
 
     fn handle(&mut self, msg: ScanFolder, _: &mut Self::Context) -> Self::Result {
-        Ok(WalkDir::new(&msg.publication_path)
+        // an unreadable library folder would look empty, which would make all of it missing
+        fs::read_dir(&msg.publication_path)
+            .map_err(|err| ScannerError::FolderError(err.to_string()))?;
+
+        let mut files = Vec::new();
+        let entries = WalkDir::new(&msg.publication_path)
             .into_iter()
             .filter_map(|e| e.ok())
-            .filter(is_accepted_file)
-            .filter(ignore_dotfile)
-            .map(|dir| File::from(&dir))
-            .collect())
+            .filter(ignore_dotfile);
+        for entry in entries {
+            if is_accepted_file(&entry) {
+                files.push(File::from(msg.library_id, &entry));
+            } else if entry.file_type().is_dir() && entry.depth() > 0 {
+                files.extend(image_folder(msg.library_id, &entry));
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// A folder that only holds images, and maybe a ComicInfo.xml, is imported as a comic. Folders
+/// with subfolders or any other kind of file are left alone.
+fn image_folder(library_id: LibraryId, dir: &DirEntry) -> Option<File> {
+    let mut size = 0;
+    let mut modified = dir.metadata().ok().as_ref().and_then(modified_time);
+    let mut pages = 0;
+    for child in fs::read_dir(dir.path()).ok()? {
+        let child = child.ok()?;
+        let name = child.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let metadata = child.metadata().ok()?;
+        if metadata.is_dir() {
+            return None;
+        }
+        if comic_info::is_comic_info(&name) {
+            continue;
+        }
+        if !comic::is_page(&name) {
+            return None;
+        }
+        pages += 1;
+        size += metadata.len() as i64;
+        modified = modified.max(modified_time(&metadata));
+    }
+    if pages == 0 {
+        return None;
     }
+
+    Some(File {
+        library_id,
+        name: dir.file_name().to_str().unwrap_or("").to_string(),
+        path: dir.path().to_str().unwrap_or("").to_string(),
+        extension: FOLDER.to_string(),
+        size,
+        modified,
+        hash: None,
+    })
 }
 
 fn ignore_dotfile(dir: &DirEntry) -> bool {
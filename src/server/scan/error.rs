@@ -1,5 +1,42 @@
+use actix::MailboxError;
+use std::fmt;
+
 #[derive(Debug)]
 pub enum ScannerError {
     EmptyCategoryError,
     NoMatchCategory,
+    ContentError,
+    ScanInProgress,
+    /// A library folder doesn't exist or can't be read.
+    FolderError(String),
+    /// Reading the file panicked, the message says where.
+    Panic(String),
+    /// The libraries couldn't be loaded, without them there is nothing to scan.
+    LibraryError(String),
+    MailboxError(MailboxError),
+}
+
+impl fmt::Display for ScannerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScannerError::EmptyCategoryError => write!(f, "There are no categories"),
+            ScannerError::NoMatchCategory => write!(f, "No category matches the file"),
+            ScannerError::ContentError => write!(f, "Unable to extract the content"),
+            ScannerError::ScanInProgress => write!(f, "Another scan is in progress"),
+            ScannerError::FolderError(message) => {
+                write!(f, "Unable to read the folder: {}", message)
+            }
+            ScannerError::Panic(message) => write!(f, "Unable to read the file: {}", message),
+            ScannerError::LibraryError(message) => {
+                write!(f, "Unable to load the libraries: {}", message)
+            }
+            ScannerError::MailboxError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<MailboxError> for ScannerError {
+    fn from(err: MailboxError) -> Self {
+        ScannerError::MailboxError(err)
+    }
 }
@@ -0,0 +1,68 @@
+use models::{MediaType, MediaTypeHint, MediaTypeId, Publication, COMIC, COMIC_FORMATS, EBOOK};
+use reader::models::Metadata;
+use std::path::{Component, Path};
+
+/// Everything needed to work out the media type of a publication.
+#[derive(Debug, Clone, Default)]
+pub struct MediaTypeDetection {
+    pub media_types: Vec<MediaType>,
+    pub hints: Vec<MediaTypeHint>,
+}
+
+impl MediaTypeDetection {
+    /// Pick the media type of a publication. A folder hint always wins. Otherwise the media type
+    /// the file claims for itself, or failing that its format, only replaces the generic ebook and
+    /// comic types: a publication in a library of magazines stays a magazine even if it's a CBZ
+    /// with a ComicInfo.xml in it.
+    pub fn detect(&self, publication: &Publication, metadata: &Metadata) -> MediaTypeId {
+        if let Some(hint) = self.folder_hint(&publication.file) {
+            return hint.media_type_id;
+        }
+
+        let is_generic = [EBOOK, COMIC]
+            .iter()
+            .filter_map(|name| self.find(name))
+            .any(|generic_id| generic_id == publication.media_type_id);
+        if !is_generic {
+            return publication.media_type_id;
+        }
+
+        let is_comic_archive = COMIC_FORMATS
+            .iter()
+            .any(|format| *format == publication.media_format);
+        let detected = match metadata.media_type {
+            Some(ref name) => self.find(name),
+            None if is_comic_archive => self.find(COMIC),
+            None => None,
+        };
+        detected.unwrap_or(publication.media_type_id)
+    }
+
+    /// The hint for the folder closest to the file.
+    fn folder_hint(&self, file: &str) -> Option<&MediaTypeHint> {
+        let folders: Vec<&str> = Path::new(file)
+            .parent()?
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect();
+        folders
+            .iter()
+            .rev()
+            .filter_map(|folder| {
+                self.hints
+                    .iter()
+                    .find(|hint| hint.folder.eq_ignore_ascii_case(folder))
+            })
+            .next()
+    }
+
+    fn find(&self, name: &str) -> Option<MediaTypeId> {
+        self.media_types
+            .iter()
+            .find(|media_type| media_type.name.eq_ignore_ascii_case(name))
+            .map(|media_type| media_type.id)
+    }
+}
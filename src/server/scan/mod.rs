@@ -1,2 +1,9 @@
 pub mod actor;
 pub mod error;
+pub mod media_type;
+pub mod options;
+pub mod pipeline;
+pub mod progress;
+pub mod report;
+pub mod rule;
+pub mod watch;
@@ -0,0 +1,61 @@
+use models::Library;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// Only errors.
+    Quiet,
+    /// Errors, imported files and a summary.
+    Normal,
+    /// Every file as it is processed.
+    Verbose,
+}
+
+impl Default for Verbosity {
+    fn default() -> Self {
+        Verbosity::Normal
+    }
+}
+
+/// Narrows down or forces what a scan does. The default is an incremental scan of every library.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Only scan this folder, it has to be inside one of the libraries.
+    pub folder: Option<PathBuf>,
+    /// Only scan files with one of these extensions, `folder` for folders of images.
+    pub extensions: Option<Vec<String>>,
+    /// Reload the metadata and thumbnail of every file, not only of the ones that changed.
+    pub refresh: bool,
+    pub verbosity: Verbosity,
+}
+
+impl ScanOptions {
+    /// Where to start scanning a library: the library itself, or the requested folder when it is
+    /// inside the library. `None` when the folder is somewhere else.
+    pub fn root(&self, library: &Library) -> Option<PathBuf> {
+        match self.folder {
+            None => Some(PathBuf::from(&library.path)),
+            Some(ref folder) => {
+                // compare the real paths, but keep the path the way the library spells it so the
+                // file paths stay comparable with the ones in the database
+                let library_path = fs::canonicalize(&library.path).ok()?;
+                let folder = fs::canonicalize(folder).ok()?;
+                folder
+                    .strip_prefix(&library_path)
+                    .ok()
+                    .map(|relative| Path::new(&library.path).join(relative))
+            }
+        }
+    }
+
+    /// Whether files of this media format, e.g. the extension of a file, are scanned.
+    pub fn has_format(&self, format: &str) -> bool {
+        match self.extensions {
+            None => true,
+            Some(ref extensions) => extensions
+                .iter()
+                .any(|accepted| accepted.eq_ignore_ascii_case(format)),
+        }
+    }
+}
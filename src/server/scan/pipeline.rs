@@ -0,0 +1,871 @@
+use actix::prelude::*;
+use config::Config;
+use db::executor::DbExecutor;
+use db::{
+    author, category, category_rule, duplicate, library, media_type, media_type_hint, publication,
+    publication_category, search, setting,
+};
+use fs::cache::Cache;
+use futures::future::{self, join_all, Future};
+use models::{Library, LibraryId, NewPublication, Publication, PublicationCategory, UNCATEGORIZED};
+use reader::models::Metadata;
+use scan::actor::{
+    extract_content::ExtractContent,
+    load_metadata::{LoadMetadata, RefreshMetadata},
+    preview_file::PreviewFile,
+    process_file::ProcessFile,
+    reconcile::{Reconcile, Reconciliation},
+    scan_folder::ScanFolder,
+    {Category, CategoryId, File, Scanner},
+};
+use scan::error::ScannerError;
+use scan::media_type::MediaTypeDetection;
+use scan::options::{ScanOptions, Verbosity};
+use scan::progress::{Progress, Stage};
+use scan::report::{FileStatus, ReportEntry};
+use scan::rule::Rule;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Scan the library folders and bring the database in line with them: new files are categorized
+/// and imported, moved files get their path rewritten, changed files get their metadata reloaded
+/// and files that are gone are marked as missing. Moved and changed files lose whatever was
+/// extracted from them to `cache`.
+///
+/// Only one scan can run at a time, a second one is refused with `ScannerError::ScanInProgress`.
+/// A file that can't be read is counted as failed in `progress` and doesn't stop the scan, but the
+/// scan is aborted when the libraries themselves can't be loaded.
+pub fn run(
+    config: Config,
+    db: Addr<DbExecutor>,
+    scanner: Addr<Scanner>,
+    progress: Progress,
+    cache: Cache,
+    options: ScanOptions,
+) -> Result<Box<Future<Item = (), Error = ScannerError>>, ScannerError> {
+    if !progress.start() {
+        return Err(ScannerError::ScanInProgress);
+    }
+
+    let db_1 = db.clone();
+    let db_2 = db.clone();
+    let db_3 = db.clone();
+    let db_4 = db.clone();
+    let db_5 = db.clone();
+    let db_6 = db.clone();
+    let db_7 = db.clone();
+    let scanner_1 = scanner.clone();
+    let scanner_2 = scanner.clone();
+    let scanner_3 = scanner.clone();
+    let scanner_4 = scanner.clone();
+    let config_1 = config.clone();
+    let config_2 = config.clone();
+    let progress_1 = progress.clone();
+    let progress_2 = progress.clone();
+    let progress_3 = progress.clone();
+    let progress_4 = progress.clone();
+    let progress_5 = progress.clone();
+    let progress_6 = progress.clone();
+    let progress_7 = progress.clone();
+    let verbosity = options.verbosity;
+
+    let task = db
+        .send(category::GetOrCreate {
+            name: UNCATEGORIZED.to_string(),
+        })
+        .from_err()
+        .and_then(move |_| survey(db_1, scanner_1, progress_1, options.clone()))
+        .and_then(move |survey| {
+            let Survey {
+                libraries,
+                categorization,
+                detection,
+                reconciliation,
+                ..
+            } = survey;
+            progress_2.update(|status| {
+                status.stage = Stage::Importing;
+                status.processed =
+                    status.found - reconciliation.new_files.len() - reconciliation.changed.len();
+            });
+            apply_reconciliation(
+                config.clone(),
+                cache,
+                scanner_2,
+                db_2,
+                progress_2,
+                verbosity,
+                detection.clone(),
+                reconciliation,
+            )
+            .map(move |files| (libraries, categorization, detection, files))
+            .from_err()
+        })
+        .and_then(move |(libraries, categorization, detection, files)| {
+            process_files(
+                scanner_3,
+                config_1,
+                progress_3,
+                verbosity,
+                &libraries,
+                &categorization,
+                files,
+            )
+            .map(move |res| (libraries, detection, res))
+            .from_err()
+        })
+        .and_then(move |(libraries, detection, res)| {
+            save_publication(db_3, progress_4, verbosity, libraries, res)
+                .map(move |res| (detection, res))
+                .from_err()
+        })
+        .and_then(move |(detection, res)| {
+            update_metadata(config_2, scanner_4, progress_5, detection, res).from_err()
+        })
+        .and_then(move |res| update_author(db_4, res).from_err())
+        .and_then(move |res| update_publication(db_5, res).from_err())
+        .and_then(move |res| save_publication_categories(db_6, res).from_err())
+        .and_then(move |_| index_content(db_7, scanner, progress_6).from_err())
+        .map(move |_| {
+            let status = progress_7.status();
+            if verbosity > Verbosity::Quiet {
+                println!(
+                    "Scan finished: {} found, {} added, {} failed",
+                    status.found, status.added, status.failed
+                );
+            }
+        });
+
+    Ok(Box::new(task.then(move |res| {
+        if let Err(ref err) = res {
+            progress.update(|status| status.errors.push(format!("Scan aborted: {}", err)));
+        }
+        progress.finish();
+        res
+    })))
+}
+
+/// Work out what `run` would do, without writing anything to the database. Files are categorized
+/// and their metadata is read the same way a scan would.
+pub fn dry_run(
+    config: Config,
+    db: Addr<DbExecutor>,
+    scanner: Addr<Scanner>,
+    options: ScanOptions,
+) -> Box<Future<Item = Vec<ReportEntry>, Error = ScannerError>> {
+    Box::new(
+        survey(db, scanner.clone(), Progress::new(), options).and_then(move |survey| {
+            let Survey {
+                libraries,
+                categorization,
+                files,
+                publications,
+                reconciliation,
+                ..
+            } = survey;
+            let category_names: HashMap<CategoryId, String> = categorization
+                .categories
+                .iter()
+                .map(|category| (category.id, category.name.clone()))
+                .collect();
+            let titles: HashMap<String, String> = publications
+                .into_iter()
+                .map(|publication| (publication.file, publication.title))
+                .collect();
+            let mut files: HashMap<String, File> = files
+                .into_iter()
+                .map(|file| (file.path.clone(), file))
+                .collect();
+
+            let mut report = Vec::new();
+            for publication in reconciliation.moved.into_iter() {
+                files.remove(&publication.file);
+                report.push(ReportEntry {
+                    title: Some(publication.title),
+                    ..ReportEntry::new(publication.file, FileStatus::Moved)
+                });
+            }
+            for publication in reconciliation.missing.into_iter() {
+                report.push(ReportEntry {
+                    title: Some(publication.title),
+                    ..ReportEntry::new(publication.file, FileStatus::Missing)
+                });
+            }
+            for file in reconciliation.ignored.into_iter() {
+                files.remove(&file.path);
+                report.push(ReportEntry::new(file.path, FileStatus::Ignored));
+            }
+
+            let mut pending: Vec<(File, FileStatus)> = reconciliation
+                .changed
+                .iter()
+                .filter_map(|publication| files.remove(&publication.file))
+                .map(|file| (file, FileStatus::Changed))
+                .collect();
+            for file in reconciliation.new_files.into_iter() {
+                files.remove(&file.path);
+                pending.push((file, FileStatus::New));
+            }
+
+            let mut previews = Vec::new();
+            for (file, status) in pending.into_iter() {
+                let path = file.path.clone();
+                let message = match process_file_message(&config, &libraries, &categorization, file)
+                {
+                    Some(message) => message,
+                    None => continue,
+                };
+                let category_names = category_names.clone();
+                let task = scanner.send(PreviewFile(message)).map(move |res| {
+                    let mut entry = ReportEntry::new(path, status);
+                    if let Ok((categorized, metadata)) = res {
+                        // changed publications keep their category, only new ones get one
+                        if let (FileStatus::New, Some(categorized)) = (status, categorized) {
+                            let name = |id| category_names.get(&id).cloned();
+                            entry.category = name(categorized.category_id);
+                            entry.matched_by = Some(categorized.matched_by);
+                            entry.score = categorized.score;
+                            entry.runner_up = categorized.runner_up.and_then(|(id, _)| name(id));
+                            entry.runner_up_score = categorized.runner_up.map(|(_, score)| score);
+                        }
+                        entry.title = metadata.title;
+                        entry.author = metadata.author;
+                    }
+                    entry
+                });
+                previews.push(task);
+            }
+
+            // whatever is left is already in the library and doesn't need any work
+            for (path, _) in files.into_iter() {
+                report.push(ReportEntry {
+                    title: titles.get(&path).cloned(),
+                    ..ReportEntry::new(path, FileStatus::Known)
+                });
+            }
+
+            join_all(previews)
+                .map(move |entries| {
+                    report.extend(entries);
+                    report.sort_by(|a, b| a.file.cmp(&b.file));
+                    report
+                })
+                .from_err()
+        }),
+    )
+}
+
+/// The library as it is on disk compared to the library in the database.
+struct Survey {
+    libraries: HashMap<LibraryId, Library>,
+    categorization: Categorization,
+    detection: MediaTypeDetection,
+    files: Vec<File>,
+    publications: Vec<Publication>,
+    reconciliation: Reconciliation,
+}
+
+fn survey(
+    db: Addr<DbExecutor>,
+    scanner: Addr<Scanner>,
+    progress: Progress,
+    options: ScanOptions,
+) -> Box<Future<Item = Survey, Error = ScannerError>> {
+    Box::new(db.send(library::List {}).from_err().and_then(move |res| {
+        // without the libraries every publication would look missing, so don't go any further
+        let libraries = match res {
+            Ok(libraries) => libraries,
+            Err(err) => {
+                return future::Either::A(future::err(ScannerError::LibraryError(err.to_string())))
+            }
+        };
+        let libraries: HashMap<LibraryId, Library> = libraries
+            .into_iter()
+            .map(|library| (library.id, library))
+            .collect();
+
+        let roots: Vec<(LibraryId, PathBuf)> = libraries
+            .values()
+            .filter_map(|library| options.root(library).map(|root| (library.id, root)))
+            .collect();
+        if let (Some(ref folder), true) = (&options.folder, roots.is_empty()) {
+            progress.update(|status| {
+                status.fail(&folder.to_string_lossy(), "Not inside any of the libraries")
+            });
+        }
+
+        let mut scans = Vec::new();
+        for (library_id, root) in roots.iter() {
+            let root = root.clone();
+            let task = scanner
+                .send(ScanFolder {
+                    library_id: *library_id,
+                    publication_path: root.to_string_lossy().to_string(),
+                })
+                .map(move |res| (root, res));
+            scans.push(task);
+        }
+
+        let task = load_categorization(db.clone())
+            .join5(
+                load_media_type_detection(db.clone()),
+                join_all(scans),
+                db.send(publication::ListAll {}),
+                db.send(duplicate::ListIgnored {}),
+            )
+            .and_then(
+                move |(categorization, detection, scans, publications, ignored)| {
+                    // libraries that couldn't be read are left out of the reconciliation, rather
+                    // than having all of their publications marked as missing
+                    let mut files = Vec::new();
+                    let mut unreadable = Vec::new();
+                    for (root, res) in scans.into_iter() {
+                        match res {
+                            Ok(found) => files.extend(found),
+                            Err(err) => {
+                                eprintln!("Unable to scan {}: {}", root.display(), err);
+                                progress
+                                    .update(|status| status.fail(&root.to_string_lossy(), &err));
+                                unreadable.push(root);
+                            }
+                        }
+                    }
+                    let files: Vec<File> = files
+                        .into_iter()
+                        .filter(|file| options.has_format(&file.extension))
+                        .collect();
+                    progress.update(|status| {
+                        status.stage = Stage::Reconciling;
+                        status.found = files.len();
+                    });
+                    // publications outside of what is scanned aren't missing, just not looked at
+                    let publications: Vec<Publication> = publications
+                        .unwrap_or_else(|err| {
+                            eprintln!("Unable to load publications: {}", err);
+                            vec![]
+                        })
+                        .into_iter()
+                        .filter(|publication| {
+                            options.folder.is_none()
+                                || roots
+                                    .iter()
+                                    .any(|(_, root)| Path::new(&publication.file).starts_with(root))
+                        })
+                        .filter(|publication| options.has_format(&publication.media_format))
+                        .filter(|publication| {
+                            !unreadable
+                                .iter()
+                                .any(|root| Path::new(&publication.file).starts_with(root))
+                        })
+                        .collect();
+                    let ignored: HashSet<String> = ignored
+                        .unwrap_or_else(|err| {
+                            eprintln!("Unable to load ignored files: {}", err);
+                            vec![]
+                        })
+                        .into_iter()
+                        .map(|ignored_file| ignored_file.file_hash)
+                        .collect();
+                    scanner
+                        .send(Reconcile {
+                            files: files.clone(),
+                            publications: publications.clone(),
+                            ignored,
+                            refresh: options.refresh,
+                        })
+                        .map(move |reconciliation| Survey {
+                            libraries,
+                            categorization,
+                            detection,
+                            files,
+                            publications,
+                            reconciliation: reconciliation.unwrap_or_else(|err| {
+                                eprintln!("Unable to reconcile the library folders: {:?}", err);
+                                Reconciliation::default()
+                            }),
+                        })
+                },
+            )
+            .from_err();
+        future::Either::B(task)
+    }))
+}
+
+/// Everything needed to pick the category of a new file.
+struct Categorization {
+    categories: Vec<Category>,
+    rules: Vec<Rule>,
+    min_score: f64,
+    uncategorized_id: Option<CategoryId>,
+}
+
+fn load_categorization(
+    db: Addr<DbExecutor>,
+) -> Box<Future<Item = Categorization, Error = MailboxError>> {
+    Box::new(
+        db.send(category::List {})
+            .join3(db.send(category_rule::List {}), db.send(setting::Get {}))
+            .map(|(categories, rules, setting)| {
+                let categories = categories.unwrap_or(vec![]);
+                let rules = rules
+                    .unwrap_or(vec![])
+                    .iter()
+                    .filter_map(|rule| {
+                        Rule::compile(rule)
+                            .map_err(|err| eprintln!("Skipping rule {}: {}", rule.pattern, err))
+                            .ok()
+                    })
+                    .collect();
+                Categorization {
+                    uncategorized_id: categories
+                        .iter()
+                        .find(|category| category.name == UNCATEGORIZED)
+                        .map(|category| category.id),
+                    categories: categories.iter().map(&Category::from).collect(),
+                    rules,
+                    min_score: setting.unwrap_or_default().min_category_score,
+                }
+            }),
+    )
+}
+
+fn load_media_type_detection(
+    db: Addr<DbExecutor>,
+) -> Box<Future<Item = MediaTypeDetection, Error = MailboxError>> {
+    Box::new(
+        db.send(media_type::List {})
+            .join(db.send(media_type_hint::List {}))
+            .map(|(media_types, hints)| MediaTypeDetection {
+                media_types: media_types.unwrap_or(vec![]),
+                hints: hints.unwrap_or(vec![]),
+            }),
+    )
+}
+
+/// Write the outcome of the reconciliation to the database: rewrite the path of moved files, mark
+/// the missing ones and reload the metadata of changed ones. Resolves to the files that are new to
+/// the library.
+fn apply_reconciliation(
+    config: Config,
+    cache: Cache,
+    scanner: Addr<Scanner>,
+    db: Addr<DbExecutor>,
+    progress: Progress,
+    verbosity: Verbosity,
+    detection: MediaTypeDetection,
+    reconciliation: Reconciliation,
+) -> Box<Future<Item = Vec<File>, Error = MailboxError>> {
+    let Reconciliation {
+        new_files,
+        moved,
+        changed,
+        updated,
+        missing,
+        ..
+    } = reconciliation;
+    if verbosity == Verbosity::Verbose {
+        for publication in moved.iter() {
+            println!("Moved to {}", publication.file);
+        }
+        for publication in missing.iter() {
+            println!("Missing {}", publication.file);
+        }
+    }
+
+    // pages extracted from the old file may not match the new one
+    for publication in moved.iter().chain(changed.iter()) {
+        if let Err(err) = cache.remove(publication.id) {
+            eprintln!("Unable to clear the cache of {}: {}", publication.file, err);
+        }
+    }
+
+    // the content has to be indexed again from the new file
+    let clear_content = db.send(search::ClearContent {
+        publication_ids: moved
+            .iter()
+            .chain(changed.iter())
+            .map(|publication| publication.id)
+            .collect(),
+    });
+
+    let mut batch = Vec::new();
+    for publication in moved.into_iter().chain(updated.into_iter()) {
+        batch.push(db.send(publication::Update { publication }));
+    }
+    let mark_missing = db.send(publication::MarkMissing {
+        publication_ids: missing.iter().map(|publication| publication.id).collect(),
+    });
+
+    Box::new(
+        join_all(batch)
+            .join3(mark_missing, clear_content)
+            .join(refresh_publications(
+                config, scanner, db, progress, verbosity, detection, changed,
+            ))
+            .map(|_| new_files),
+    )
+}
+
+fn refresh_publications(
+    config: Config,
+    scanner: Addr<Scanner>,
+    db: Addr<DbExecutor>,
+    progress: Progress,
+    verbosity: Verbosity,
+    detection: MediaTypeDetection,
+    publications: Vec<Publication>,
+) -> Box<Future<Item = Vec<Result<(), actix_web::Error>>, Error = MailboxError>> {
+    let mut batch = Vec::new();
+    for publication in publications.into_iter() {
+        let db = db.clone();
+        let progress = progress.clone();
+        let file = publication.file.clone();
+        let task = scanner
+            .send(RefreshMetadata {
+                config: config.clone(),
+                detection: detection.clone(),
+                publication,
+            })
+            .then(move |res| {
+                let res = or_mailbox_error(res);
+                report_file(&progress, verbosity, "Refreshed", &file, &res);
+                future::ok::<_, MailboxError>(res)
+            })
+            .and_then(move |res| match res {
+                Ok((publication, metadata)) => future::Either::A(
+                    resolve_author(db.clone(), publication, metadata.author)
+                        .and_then(move |publication| db.send(publication::Update { publication })),
+                ),
+                Err(err) => future::Either::B(future::ok(Err(
+                    actix_web::error::ErrorInternalServerError(format!("{:?}", err)),
+                ))),
+            });
+        batch.push(task);
+    }
+
+    Box::new(join_all(batch))
+}
+
+fn process_files(
+    scanner: Addr<Scanner>,
+    config: Config,
+    progress: Progress,
+    verbosity: Verbosity,
+    libraries: &HashMap<LibraryId, Library>,
+    categorization: &Categorization,
+    files: Vec<File>,
+) -> Box<Future<Item = Vec<Result<(File, CategoryId), ScannerError>>, Error = MailboxError>> {
+    let mut batch = Vec::new();
+    for file in files.into_iter() {
+        let path = file.path.clone();
+        let message = match process_file_message(&config, libraries, categorization, file) {
+            Some(message) => message,
+            None => continue,
+        };
+        let progress = progress.clone();
+        let task = scanner.send(message).then(move |res| {
+            let res = or_mailbox_error(res);
+            report_file(&progress, verbosity, "Categorized", &path, &res);
+            future::ok::<_, MailboxError>(res)
+        });
+        batch.push(task);
+    }
+    Box::new(join_all(batch))
+}
+
+/// A scanner thread that went away fails the file it was working on, not the whole scan.
+fn or_mailbox_error<T>(
+    res: Result<Result<T, ScannerError>, MailboxError>,
+) -> Result<T, ScannerError> {
+    res.unwrap_or_else(|err| Err(ScannerError::Panic(err.to_string())))
+}
+
+/// Count a file that went through the scanner as processed, or as failed.
+fn report_file<T>(
+    progress: &Progress,
+    verbosity: Verbosity,
+    action: &str,
+    file: &str,
+    res: &Result<T, ScannerError>,
+) {
+    match res {
+        Ok(_) if verbosity == Verbosity::Verbose => println!("{} {}", action, file),
+        Ok(_) => {}
+        Err(err) => eprintln!("Unable to scan {}: {}", file, err),
+    }
+    progress.update(|status| {
+        status.processed += 1;
+        if let Err(err) = res {
+            status.fail(file, err);
+        }
+        status.current_file = Some(file.to_string());
+    });
+}
+
+fn process_file_message(
+    config: &Config,
+    libraries: &HashMap<LibraryId, Library>,
+    categorization: &Categorization,
+    file: File,
+) -> Option<ProcessFile> {
+    let library = libraries.get(&file.library_id)?;
+    Some(ProcessFile {
+        publication_path: library.path.clone(),
+        config: config.clone(),
+        categories: categorization.categories.clone(),
+        rules: categorization.rules.clone(),
+        min_score: categorization.min_score,
+        fallback_category_id: library.category_id.or(categorization.uncategorized_id),
+        file,
+    })
+}
+
+fn save_publication(
+    db: Addr<DbExecutor>,
+    progress: Progress,
+    verbosity: Verbosity,
+    libraries: HashMap<LibraryId, Library>,
+    files: Vec<Result<(File, CategoryId), ScannerError>>,
+) -> Box<
+    Future<Item = Result<Vec<(Publication, CategoryId)>, actix_web::Error>, Error = MailboxError>,
+> {
+    let mut batch: Vec<NewPublication> = Vec::new();
+    let mut file_map: HashMap<String, CategoryId> = HashMap::new();
+    for result in files.into_iter() {
+        if let Ok((file, category_id)) = result {
+            let publication = NewPublication {
+                isbn: "".to_string(),
+                title: file.name.clone(),
+                media_type_id: libraries
+                    .get(&file.library_id)
+                    .map(|library| library.media_type_id)
+                    .unwrap_or(1),
+                media_format: file.extension.clone(),
+                author_id: 1,
+                thumbnail: None,
+                file: file.path.clone(),
+                timestamp: None,
+                language: None,
+                series: None,
+                issue: None,
+                file_size: Some(file.size),
+                file_modified: file.modified,
+                file_hash: file.hash.clone(),
+            };
+
+            file_map.insert(publication.file.clone(), category_id);
+            batch.push(publication);
+        }
+    }
+
+    Box::new(db.send(publication::CreateBatch(batch)).map(move |result| {
+        progress.update(|status| {
+            status.stage = Stage::LoadingMetadata;
+            match result {
+                Ok(ref created) => {
+                    status.added += created.publications.len();
+                    let added: HashSet<&str> = created
+                        .publications
+                        .iter()
+                        .map(|publication| publication.file.as_str())
+                        .collect();
+                    for file in file_map.keys() {
+                        if !added.contains(file.as_str()) {
+                            match created.refused.get(file) {
+                                Some(reason) => status.fail(file, reason),
+                                None => status.fail(file, "Unable to add the publication"),
+                            }
+                        }
+                    }
+                }
+                Err(ref err) => {
+                    for file in file_map.keys() {
+                        status.fail(file, err);
+                    }
+                }
+            }
+        });
+        if let (Ok(ref created), true) = (&result, verbosity > Verbosity::Quiet) {
+            for publication in created.publications.iter() {
+                println!("Added {}", publication.file);
+            }
+        }
+        result.map(|created| {
+            created
+                .publications
+                .into_iter()
+                .map(|publication| {
+                    let category_id = file_map.get(&publication.file).unwrap();
+                    (publication, *category_id)
+                })
+                .collect()
+        })
+    }))
+}
+
+fn update_metadata(
+    config: Config,
+    scanner: Addr<Scanner>,
+    progress: Progress,
+    detection: MediaTypeDetection,
+    result: Result<Vec<(Publication, CategoryId)>, actix_web::Error>,
+) -> Box<
+    Future<
+        Item = Vec<Result<(Publication, CategoryId, Metadata), ScannerError>>,
+        Error = MailboxError,
+    >,
+> {
+    let mut batch = Vec::new();
+    let publications = result.unwrap_or_else(|err| {
+        println!("Unable to save publications: {}", err);
+        vec![]
+    });
+    for data in publications.into_iter() {
+        let scanner = scanner.clone();
+        let progress = progress.clone();
+        let path = data.0.file.clone();
+        let task = scanner
+            .send(LoadMetadata {
+                config: config.clone(),
+                detection: detection.clone(),
+                publication: data.0,
+                category_id: data.1,
+            })
+            .then(move |res| {
+                let res = or_mailbox_error(res);
+                progress.update(|status| {
+                    if let Err(ref err) = res {
+                        eprintln!("Unable to load metadata of {}: {}", path, err);
+                        status.fail(&path, err);
+                    }
+                    status.current_file = Some(path);
+                });
+                future::ok::<_, MailboxError>(res)
+            });
+        batch.push(task);
+    }
+
+    Box::new(join_all(batch))
+}
+
+fn update_author(
+    db: Addr<DbExecutor>,
+    result: Vec<Result<(Publication, CategoryId, Metadata), ScannerError>>,
+) -> Box<Future<Item = Vec<Result<(Publication, CategoryId), ScannerError>>, Error = MailboxError>>
+{
+    let mut batch = Vec::new();
+    for data in result.into_iter() {
+        let task = match data {
+            Ok((publication, category_id, metadata)) => future::Either::A(
+                resolve_author(db.clone(), publication, metadata.author)
+                    .map(move |publication| Ok((publication, category_id))),
+            ),
+            Err(err) => future::Either::B(future::ok(Err(err))),
+        };
+        batch.push(task);
+    }
+
+    Box::new(join_all(batch))
+}
+
+fn resolve_author(
+    db: Addr<DbExecutor>,
+    publication: Publication,
+    author_name: Option<String>,
+) -> Box<Future<Item = Publication, Error = MailboxError>> {
+    match author_name {
+        Some(name) => Box::new(db.send(author::GetOrCreate { name }).map(move |res| {
+            let author_id = res.map(|author| author.id).unwrap_or(publication.author_id);
+            Publication {
+                author_id,
+                ..publication
+            }
+        })),
+        None => Box::new(future::ok(publication)),
+    }
+}
+
+fn update_publication(
+    db: Addr<DbExecutor>,
+    result: Vec<Result<(Publication, CategoryId), ScannerError>>,
+) -> Box<Future<Item = Vec<Result<(Publication, CategoryId), ScannerError>>, Error = MailboxError>>
+{
+    let mut batch = Vec::new();
+    for data in result.into_iter() {
+        let task = match data {
+            Ok(data) => future::Either::A(
+                db.send(publication::Update {
+                    publication: data.0.clone(),
+                })
+                .map(|_| Ok(data)),
+            ),
+            Err(err) => future::Either::B(future::ok(Err(err))),
+        };
+        batch.push(task)
+    }
+
+    Box::new(join_all(batch))
+}
+
+fn save_publication_categories(
+    db: Addr<DbExecutor>,
+    publications: Vec<Result<(Publication, CategoryId), ScannerError>>,
+) -> Box<Future<Item = Result<(), actix_web::Error>, Error = MailboxError>> {
+    let publication_categories: Vec<PublicationCategory> = publications
+        .into_iter()
+        .filter_map(|res| res.ok())
+        .map(|(publication, category_id)| PublicationCategory {
+            publication_id: publication.id,
+            category_id: category_id,
+        })
+        .collect();
+    Box::new(db.send(publication_category::CreateBatch(publication_categories)))
+}
+
+fn index_content(
+    db: Addr<DbExecutor>,
+    scanner: Addr<Scanner>,
+    progress: Progress,
+) -> Box<Future<Item = Vec<Result<(), actix_web::Error>>, Error = MailboxError>> {
+    progress.update(|status| status.stage = Stage::Indexing);
+    Box::new(
+        db.send(search::ListUnindexed {})
+            .and_then(move |publications| {
+                let mut batch = Vec::new();
+                for publication in publications.unwrap_or(vec![]).into_iter() {
+                    let db = db.clone();
+                    let progress = progress.clone();
+                    let failure = search::RecordIndexFailure {
+                        publication_id: publication.id,
+                        file_size: publication.file_size,
+                        file_modified: publication.file_modified,
+                    };
+                    let task = scanner
+                        .send(ExtractContent { publication })
+                        .then(move |res| match or_mailbox_error(res) {
+                            // nothing to search for, don't extract it again on every scan
+                            Ok((_, ref pages))
+                                if pages.iter().all(|page| page.trim().is_empty()) =>
+                            {
+                                future::Either::B(db.send(failure))
+                            }
+                            Ok((publication, pages)) => {
+                                progress.update(|status| {
+                                    status.current_file = Some(publication.file.clone())
+                                });
+                                future::Either::A(db.send(search::IndexContent {
+                                    publication_id: publication.id,
+                                    pages,
+                                }))
+                            }
+                            Err(err) => {
+                                eprintln!("Unable to index content: {:?}", err);
+                                future::Either::B(db.send(failure))
+                            }
+                        });
+                    batch.push(task);
+                }
+                join_all(batch)
+            }),
+    )
+}
@@ -0,0 +1,109 @@
+use chrono::{NaiveDateTime, Utc};
+use std::fmt::Display;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Only the first errors are kept, a broken library shouldn't make the status huge.
+const MAX_ERRORS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Idle,
+    Scanning,
+    Reconciling,
+    Importing,
+    LoadingMetadata,
+    Indexing,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScanStatus {
+    pub running: bool,
+    pub stage: Stage,
+    /// Files found in the publication folder.
+    pub found: usize,
+    /// Files that were checked against the library, whether they needed any work or not.
+    pub processed: usize,
+    /// Publications added to the library.
+    pub added: usize,
+    pub failed: usize,
+    /// What went wrong with the files that failed.
+    pub errors: Vec<String>,
+    pub current_file: Option<String>,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl ScanStatus {
+    pub fn fail<E: Display>(&mut self, file: &str, error: E) {
+        self.failed += 1;
+        if self.errors.len() < MAX_ERRORS {
+            self.errors.push(format!("{}: {}", file, error));
+        }
+    }
+}
+
+impl Default for ScanStatus {
+    fn default() -> Self {
+        ScanStatus {
+            running: false,
+            stage: Stage::Idle,
+            found: 0,
+            processed: 0,
+            added: 0,
+            failed: 0,
+            errors: vec![],
+            current_file: None,
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+/// Progress of the current (or last) scan, shared between the scan pipeline and whoever wants to
+/// report on it. Only one scan can run at a time.
+#[derive(Debug, Clone, Default)]
+pub struct Progress(Arc<Mutex<ScanStatus>>);
+
+impl Progress {
+    pub fn new() -> Self {
+        Progress::default()
+    }
+
+    pub fn status(&self) -> ScanStatus {
+        self.lock().clone()
+    }
+
+    /// Reset the counters for a new scan. Returns `false` when another scan is still running.
+    pub fn start(&self) -> bool {
+        let mut status = self.lock();
+        if status.running {
+            return false;
+        }
+        *status = ScanStatus {
+            running: true,
+            stage: Stage::Scanning,
+            started_at: Some(Utc::now().naive_utc()),
+            ..ScanStatus::default()
+        };
+        true
+    }
+
+    pub fn update<F: FnOnce(&mut ScanStatus)>(&self, f: F) {
+        f(&mut self.lock());
+    }
+
+    pub fn finish(&self) {
+        let mut status = self.lock();
+        status.running = false;
+        status.stage = Stage::Done;
+        status.current_file = None;
+        status.finished_at = Some(Utc::now().naive_utc());
+    }
+
+    fn lock(&self) -> MutexGuard<ScanStatus> {
+        // a panic while holding the lock can't leave the counters in a state worth refusing
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
@@ -0,0 +1,65 @@
+use csv;
+use scan::actor::process_file::MatchedBy;
+use serde_json;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// Not in the library yet, would be imported.
+    New,
+    /// In the library, but the file changed and its metadata would be reloaded.
+    Changed,
+    /// In the library under another path, which would be updated.
+    Moved,
+    /// In the library and unchanged.
+    Known,
+    /// In the library, but the file is gone.
+    Missing,
+    /// Merged into another publication, would be skipped.
+    Ignored,
+}
+
+/// What a scan would do with a single file. Kept flat so it can be written as CSV.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportEntry {
+    pub file: String,
+    pub status: FileStatus,
+    pub category: Option<String>,
+    pub matched_by: Option<MatchedBy>,
+    pub score: Option<f64>,
+    pub runner_up: Option<String>,
+    pub runner_up_score: Option<f64>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+impl ReportEntry {
+    pub fn new(file: String, status: FileStatus) -> Self {
+        ReportEntry {
+            file,
+            status,
+            category: None,
+            matched_by: None,
+            score: None,
+            runner_up: None,
+            runner_up_score: None,
+            title: None,
+            author: None,
+        }
+    }
+}
+
+pub fn write_json<W: io::Write>(writer: W, report: &[ReportEntry]) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, report)?;
+    Ok(())
+}
+
+pub fn write_csv<W: io::Write>(writer: W, report: &[ReportEntry]) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for entry in report.iter() {
+        writer.serialize(entry)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use glob::{self, MatchOptions};
+use models::{CategoryId, CategoryRule, GLOB, REGEX};
+use regex::Regex;
+use std::fmt;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+#[derive(Debug)]
+pub enum RuleError {
+    UnknownKind(String),
+    InvalidGlob(glob::PatternError),
+    InvalidRegex(regex::Error),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::UnknownKind(kind) => write!(f, "Unknown rule kind {}", kind),
+            RuleError::InvalidGlob(err) => write!(f, "Invalid glob: {}", err),
+            RuleError::InvalidRegex(err) => write!(f, "Invalid regex: {}", err),
+        }
+    }
+}
+
+/// A category rule with its pattern compiled, ready to be matched against file paths.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub pattern: Pattern,
+    pub category_id: CategoryId,
+}
+
+impl Rule {
+    pub fn compile(rule: &CategoryRule) -> Result<Self, RuleError> {
+        let pattern = match rule.kind.as_ref() {
+            GLOB => {
+                Pattern::Glob(glob::Pattern::new(&rule.pattern).map_err(RuleError::InvalidGlob)?)
+            }
+            REGEX => Pattern::Regex(Regex::new(&rule.pattern).map_err(RuleError::InvalidRegex)?),
+            kind => return Err(RuleError::UnknownKind(kind.to_string())),
+        };
+        Ok(Rule {
+            pattern,
+            category_id: rule.category_id,
+        })
+    }
+
+    /// `path` is relative to the library folder and uses `/` as separator. Globs have to match the
+    /// whole path, ignoring case, while a regex only has to match part of it.
+    pub fn matches(&self, path: &str) -> bool {
+        match &self.pattern {
+            Pattern::Glob(pattern) => pattern.matches_with(path, GLOB_OPTIONS),
+            Pattern::Regex(regex) => regex.is_match(path),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use actix::prelude::*;
+use config::Config;
+use db::executor::DbExecutor;
+use db::library;
+use fs::cache::Cache;
+use notify::{self, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use reader::{comic, comic_info};
+use scan::actor::Scanner;
+use scan::options::ScanOptions;
+use scan::pipeline;
+use scan::progress::Progress;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// How long the file system has to be quiet before a batch of changes triggers a rescan. Copying a
+/// large file produces a stream of writes, so this needs to be long enough to only see the end of
+/// it.
+const DEBOUNCE_SECONDS: u64 = 2;
+
+/// How often the library folders are read again from the database. The server tells the watch
+/// right away when they are changed through the settings, this catches the changes made while
+/// watching from the scanner.
+const LIBRARY_POLL_SECONDS: u64 = 60;
+
+/// Keeps the library in sync with the library folders by rescanning whenever something in them
+/// changes. Only the folders the changes happened in are scanned, one after the other, so new
+/// files are imported and deleted or renamed files are reconciled without walking the rest of the
+/// library. Changes that happen while a scan is running are picked up once it finishes.
+pub struct Watch {
+    config: Config,
+    db: Addr<DbExecutor>,
+    scanner: Addr<Scanner>,
+    progress: Progress,
+    cache: Cache,
+    /// A scan of the changes is scheduled or running.
+    pending: bool,
+    /// Every library has to be scanned, e.g. because the watcher lost track of the changes.
+    full_rescan: bool,
+    /// Folders that changed since they were last scanned.
+    changed: HashSet<PathBuf>,
+    watcher: Option<RecommendedWatcher>,
+    paths: Vec<String>,
+    /// The libraries were read at least once.
+    loaded: bool,
+}
+
+impl Watch {
+    pub fn new(
+        config: Config,
+        db: Addr<DbExecutor>,
+        scanner: Addr<Scanner>,
+        progress: Progress,
+        cache: Cache,
+    ) -> Self {
+        Watch {
+            config,
+            db,
+            scanner,
+            progress,
+            cache,
+            pending: false,
+            full_rescan: false,
+            changed: HashSet::new(),
+            watcher: None,
+            paths: vec![],
+            loaded: false,
+        }
+    }
+
+    /// Folder to scan for a changed path: the folder it is in, or for the page of a folder of
+    /// images the folder above, since that is where the folder is found as a publication. Folders
+    /// that are gone are replaced by the closest one that is still there, and nothing is scanned
+    /// outside of the libraries.
+    fn folder_to_scan(&self, path: &Path) -> Option<PathBuf> {
+        let library = self
+            .paths
+            .iter()
+            .map(Path::new)
+            .find(|library| path.starts_with(library))?;
+        let name = path.file_name()?.to_string_lossy();
+        let mut folder = path.parent()?;
+        if comic::is_page(&name) || comic_info::is_comic_info(&name) {
+            folder = folder.parent()?;
+        }
+        while !folder.is_dir() && folder.starts_with(library) {
+            folder = folder.parent()?;
+        }
+        if folder.starts_with(library) {
+            Some(folder.to_path_buf())
+        } else {
+            Some(library.to_path_buf())
+        }
+    }
+
+    /// Wait for the file system to settle, then scan what changed.
+    fn schedule(&mut self, ctx: &mut Context<Self>) {
+        if self.pending {
+            return;
+        }
+        self.pending = true;
+        ctx.run_later(Duration::from_secs(DEBOUNCE_SECONDS), |act, ctx| {
+            act.pending = false;
+            act.scan_next(ctx);
+        });
+    }
+
+    /// Scan everything when asked to, otherwise the next changed folder that isn't inside another
+    /// one, which takes the folders inside it along.
+    fn scan_next(&mut self, ctx: &mut Context<Self>) {
+        let folder = match self.full_rescan {
+            true => None,
+            false => {
+                let folder = self
+                    .changed
+                    .iter()
+                    .find(|folder| {
+                        !self
+                            .changed
+                            .iter()
+                            .any(|other| other != *folder && folder.starts_with(other))
+                    })
+                    .cloned();
+                match folder {
+                    Some(folder) => Some(folder),
+                    None => return,
+                }
+            }
+        };
+
+        let task = pipeline::run(
+            self.config.clone(),
+            self.db.clone(),
+            self.scanner.clone(),
+            self.progress.clone(),
+            self.cache.clone(),
+            ScanOptions {
+                folder: folder.clone(),
+                ..ScanOptions::default()
+            },
+        );
+        match task {
+            Ok(task) => {
+                match folder {
+                    Some(folder) => self.changed.retain(|changed| !changed.starts_with(&folder)),
+                    None => {
+                        self.full_rescan = false;
+                        self.changed.clear();
+                    }
+                }
+                // the scan counts as pending until it is done, changes coming in meanwhile are
+                // scanned right after it
+                self.pending = true;
+                ctx.spawn(task.into_actor(self).then(|res, act, ctx| {
+                    if let Err(err) = res {
+                        println!("Rescan failed: {}", err);
+                    }
+                    act.pending = false;
+                    act.scan_next(ctx);
+                    actix::fut::ok(())
+                }));
+            }
+            // a scan started through the API is running, check back once it had time to finish
+            Err(_) => self.schedule(ctx),
+        }
+    }
+
+    /// Start watching the folders that were added and stop watching the ones that were removed.
+    fn update_watches(&mut self, paths: Vec<String>) {
+        let watched = &self.paths;
+        if let Some(ref mut watcher) = self.watcher {
+            for path in watched.iter().filter(|path| !paths.contains(path)) {
+                match watcher.unwatch(path) {
+                    Ok(_) => println!("Stopped watching {}", path),
+                    Err(err) => println!("Unable to stop watching {}: {}", path, err),
+                }
+            }
+            for path in paths.iter().filter(|path| !watched.contains(path)) {
+                match watcher.watch(path, RecursiveMode::Recursive) {
+                    Ok(_) => println!("Watching {} for changes", path),
+                    Err(err) => println!("Unable to watch {}: {}", path, err),
+                }
+            }
+        }
+        self.paths = paths;
+    }
+}
+
+impl Actor for Watch {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let (tx, rx) = channel();
+        match notify::watcher(tx, Duration::from_secs(DEBOUNCE_SECONDS)) {
+            Ok(watcher) => {
+                self.watcher = Some(watcher);
+                let addr = ctx.address();
+                thread::spawn(move || forward_events(rx, addr));
+            }
+            Err(err) => println!("Unable to create watcher: {}", err),
+        }
+
+        ctx.notify(ReloadLibraries);
+        ctx.run_interval(Duration::from_secs(LIBRARY_POLL_SECONDS), |_act, ctx| {
+            ctx.notify(ReloadLibraries)
+        });
+
+        // catch up with whatever changed while we weren't watching
+        ctx.notify(Rescan);
+    }
+}
+
+/// Read the library folders again and watch the ones that changed, scanning the new ones.
+pub struct ReloadLibraries;
+impl Message for ReloadLibraries {
+    type Result = ();
+}
+
+impl Handler<ReloadLibraries> for Watch {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ReloadLibraries, ctx: &mut Self::Context) -> Self::Result {
+        let task = self
+            .db
+            .send(library::List {})
+            .into_actor(self)
+            .map(|res, act, ctx| match res {
+                Ok(libraries) => {
+                    let paths: Vec<String> =
+                        libraries.into_iter().map(|library| library.path).collect();
+                    let added: Vec<PathBuf> = paths
+                        .iter()
+                        .filter(|path| !act.paths.contains(path))
+                        .map(PathBuf::from)
+                        .collect();
+                    act.update_watches(paths);
+                    // the first time around the full rescan on start covers them
+                    if act.loaded && !added.is_empty() {
+                        act.changed.extend(added);
+                        act.schedule(ctx);
+                    }
+                    act.loaded = true;
+                }
+                Err(err) => println!("Unable to load libraries: {}", err),
+            })
+            .map_err(|err, _act, _ctx| println!("Unable to load libraries: {:?}", err));
+        ctx.spawn(task);
+    }
+}
+
+/// Scan every library.
+pub struct Rescan;
+impl Message for Rescan {
+    type Result = ();
+}
+
+impl Handler<Rescan> for Watch {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Rescan, ctx: &mut Self::Context) -> Self::Result {
+        self.full_rescan = true;
+        self.schedule(ctx);
+    }
+}
+
+/// Something changed at `path`, scan the folder it is in.
+pub struct Changed {
+    pub path: PathBuf,
+}
+impl Message for Changed {
+    type Result = ();
+}
+
+impl Handler<Changed> for Watch {
+    type Result = ();
+
+    fn handle(&mut self, msg: Changed, ctx: &mut Self::Context) -> Self::Result {
+        match self.folder_to_scan(&msg.path) {
+            Some(folder) => {
+                self.changed.insert(folder);
+                self.schedule(ctx);
+            }
+            None => println!("Ignoring change outside of the libraries: {:?}", msg.path),
+        }
+    }
+}
+
+fn forward_events(rx: Receiver<DebouncedEvent>, addr: Addr<Watch>) {
+    for event in rx.iter() {
+        let paths = match event {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Remove(path) => vec![path],
+            DebouncedEvent::Rename(from, to) => vec![from, to],
+            // events were lost, only a full rescan can tell what changed
+            DebouncedEvent::Rescan => {
+                addr.do_send(Rescan);
+                vec![]
+            }
+            DebouncedEvent::Error(err, path) => {
+                println!("Watch error on {:?}: {}", path, err);
+                vec![]
+            }
+            _ => vec![],
+        };
+        for path in paths.into_iter().filter(|path| !is_dotfile(path)) {
+            addr.do_send(Changed { path });
+        }
+    }
+}
+
+fn is_dotfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with("."))
+        .unwrap_or(false)
+}
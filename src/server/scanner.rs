@@ -1,201 +1,184 @@
 extern crate actix;
-extern crate actix_web;
+extern crate clap;
 extern crate futures;
 extern crate pustaka;
-extern crate strsim;
-extern crate walkdir;
 
 use actix::prelude::*;
-use futures::future::{join_all, Future};
-use pustaka::config::{self, Config};
+use clap::{App, Arg};
+use futures::Future;
+use pustaka::config;
 use pustaka::db::executor::DbExecutor;
-use pustaka::db::setting;
-use pustaka::db::{publication, publication_category};
-use pustaka::models::{NewPublication, Publication, PublicationCategory};
-use pustaka::scan::actor::{
-    load_metadata::LoadMetadata,
-    process_file::ProcessFile,
-    scan_folder::ScanFolder,
-    {Category, CategoryId, File, Scanner},
-};
-use pustaka::scan::error::ScannerError;
-use std::collections::HashMap;
+use pustaka::fs::cache::Cache;
+use pustaka::models::FOLDER;
+use pustaka::scan::actor::scan_folder::ACCEPTED_EXTENSIONS;
+use pustaka::scan::actor::Scanner;
+use pustaka::scan::options::{ScanOptions, Verbosity};
+use pustaka::scan::pipeline;
+use pustaka::scan::progress::Progress;
+use pustaka::scan::report;
+use pustaka::scan::watch::Watch;
+use std::io;
+use std::path::PathBuf;
+use std::process;
 
 fn main() {
+    let matches = App::new("pustaka-scanner")
+        .about("Imports the publications found in the library folders")
+        .arg(
+            Arg::with_name("folder")
+                .short("f")
+                .long("folder")
+                .value_name("FOLDER")
+                .help("Only scan this folder, it has to be inside one of the libraries"),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .short("j")
+                .long("jobs")
+                .value_name("N")
+                .default_value("5")
+                .validator(|jobs| match jobs.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => Ok(()),
+                    _ => Err("has to be a positive number".to_string()),
+                })
+                .help("How many files to process at the same time"),
+        )
+        .arg(
+            Arg::with_name("refresh")
+                .long("refresh")
+                .help("Reload the metadata and thumbnails of files that didn't change"),
+        )
+        .arg(
+            Arg::with_name("ext")
+                .short("e")
+                .long("ext")
+                .value_name("EXT")
+                .multiple(true)
+                .use_delimiter(true)
+                .validator(|ext| {
+                    let ext = ext.to_lowercase();
+                    match ext == FOLDER || ACCEPTED_EXTENSIONS.contains(ext.as_str()) {
+                        true => Ok(()),
+                        false => Err(format!("{} files aren't supported", ext)),
+                    }
+                })
+                .help("Only scan files with these extensions, `folder` for folders of images"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .conflicts_with("quiet")
+                .help("Print every file as it is processed"),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .help("Only print errors"),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .conflicts_with_all(&["dry-run", "folder", "ext", "refresh"])
+                .help("Keep running and rescan whenever a library folder changes"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help("Report what a scan would do without changing anything"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .possible_values(&["json", "csv"])
+                .default_value("json")
+                .requires("dry-run")
+                .help("Format of the dry run report"),
+        )
+        .get_matches();
+
+    let jobs = matches
+        .value_of("jobs")
+        .and_then(|jobs| jobs.parse().ok())
+        .unwrap_or(5);
+    let options = ScanOptions {
+        folder: matches.value_of("folder").map(PathBuf::from),
+        extensions: matches
+            .values_of("ext")
+            .map(|extensions| extensions.map(|ext| ext.to_lowercase()).collect()),
+        refresh: matches.is_present("refresh"),
+        verbosity: match (matches.is_present("verbose"), matches.is_present("quiet")) {
+            (true, _) => Verbosity::Verbose,
+            (_, true) => Verbosity::Quiet,
+            _ => Verbosity::Normal,
+        },
+    };
+
     let sys = System::new("pustaka-scanner");
     let config = config::get_config();
     let pool = pustaka::db::create_db_pool(&config.database);
     let db = SyncArbiter::start(1, move || DbExecutor(pool.clone()));
-    let db_1 = db.clone();
-    let db_2 = db.clone();
-    let db_3 = db.clone();
-    let db_4 = db.clone();
+    let scanner = SyncArbiter::start(jobs, || Scanner {});
+    let progress = Progress::new();
+    let cache = Cache::new(&config);
 
-    let scanner = SyncArbiter::start(5, || Scanner {});
-    let scanner_1 = scanner.clone();
-    let scanner_2 = scanner.clone();
-    let task = db
-        .send(setting::Get {})
-        .and_then(move |res| {
-            let publication_path = res
-                .ok()
-                .and_then(|setting| setting.publication_path)
-                .unwrap_or("".to_string());
-            let config = config::get_config();
-            let config_1 = config.clone();
-            let config_2 = config.clone();
-
-            db_1.send(pustaka::db::category::List {})
-                .join(scanner.clone().send(ScanFolder {
-                    publication_path: publication_path.clone(),
-                }))
-                .and_then(|(categories, files)| {
-                    process_files(scanner_1, config_1, publication_path, categories, files)
+    if matches.is_present("watch") {
+        Watch::new(config, db, scanner, progress, cache).start();
+    } else if matches.is_present("dry-run") {
+        let csv = matches.value_of("format") == Some("csv");
+        let task = pipeline::dry_run(config, db, scanner, options)
+            .map(move |entries| {
+                let res = match csv {
+                    true => {
+                        report::write_csv(io::stdout(), &entries).map_err(|err| err.to_string())
+                    }
+                    false => {
+                        report::write_json(io::stdout(), &entries).map_err(|err| err.to_string())
+                    }
+                };
+                match res {
+                    Ok(_) => System::current().stop(),
+                    Err(err) => {
+                        eprintln!("Unable to write report: {}", err);
+                        System::current().stop_with_code(1)
+                    }
+                }
+            })
+            .map_err(|err| {
+                eprintln!("{:?}", err);
+                System::current().stop_with_code(1)
+            });
+        Arbiter::spawn(task);
+    } else {
+        match pipeline::run(config, db, scanner, progress.clone(), cache, options) {
+            Ok(task) => Arbiter::spawn(
+                task.map(move |_| {
+                    let status = progress.status();
+                    if status.failed == 0 {
+                        return System::current().stop();
+                    }
+                    eprintln!("{} of {} files failed:", status.failed, status.found);
+                    for error in status.errors.iter() {
+                        eprintln!("  {}", error);
+                    }
+                    if status.failed > status.errors.len() {
+                        eprintln!("  and {} more", status.failed - status.errors.len());
+                    }
+                    System::current().stop_with_code(1)
                 })
-                .and_then(|res| save_publication(db_2, res))
-                .and_then(|res| update_metadata(config_2, scanner_2, res))
-                .and_then(|res| update_publication(db_3, res))
-                .and_then(|res| save_publication_categories(db_4, res))
-        })
-        .map(|_| System::current().stop())
-        .map_err(|err| println!("{:?}", err));
-
-    Arbiter::spawn(task);
-    sys.run();
-}
-
-fn process_files(
-    scanner: Addr<Scanner>,
-    config: Config,
-    publication_path: String,
-    categories: Result<Vec<pustaka::models::Category>, actix_web::Error>,
-    files: Result<Vec<File>, ScannerError>,
-) -> Box<Future<Item = Vec<Result<(File, CategoryId), ScannerError>>, Error = actix::MailboxError>>
-{
-    let files = files.unwrap();
-    let categories: Vec<Category> = categories
-        .unwrap_or(vec![])
-        .iter()
-        .map(&Category::from)
-        .collect();
-    let mut batch = Vec::new();
-    for file in files.iter() {
-        let scanner = scanner.clone();
-        let task = scanner.send(ProcessFile {
-            publication_path: publication_path.clone(),
-            config: config.clone(),
-            categories: categories.clone(),
-            file: file.clone(),
-        });
-        batch.push(task);
-    }
-    Box::new(join_all(batch))
-}
-
-fn save_publication(
-    db: Addr<DbExecutor>,
-    files: Vec<Result<(File, CategoryId), ScannerError>>,
-) -> Box<
-    Future<
-        Item = Result<Vec<(Publication, CategoryId)>, actix_web::Error>,
-        Error = actix::MailboxError,
-    >,
-> {
-    let mut batch: Vec<NewPublication> = Vec::new();
-    let mut file_map: HashMap<String, CategoryId> = HashMap::new();
-    for result in files.into_iter() {
-        if let Ok((file, category_id)) = result {
-            let publication = NewPublication {
-                isbn: "".to_string(),
-                title: file.name.clone(),
-                media_type_id: 1,
-                media_format: file.extension.clone(),
-                author_id: 1,
-                thumbnail: None,
-                file: file.path.clone(),
-                timestamp: None,
-            };
-
-            file_map.insert(publication.file.clone(), category_id);
-            batch.push(publication);
+                .map_err(|err| {
+                    eprintln!("Scan failed: {}", err);
+                    System::current().stop_with_code(1)
+                }),
+            ),
+            Err(err) => {
+                eprintln!("Unable to start scan: {:?}", err);
+                process::exit(1);
+            }
         }
     }
 
-    Box::new(db.send(publication::CreateBatch(batch)).map(move |result| {
-        result.map(|publications| {
-            publications
-                .into_iter()
-                .map(|publication| {
-                    let category_id = file_map.get(&publication.file).unwrap();
-                    (publication, *category_id)
-                })
-                .collect()
-        })
-    }))
-}
-
-fn update_metadata(
-    config: Config,
-    scanner: Addr<Scanner>,
-    result: Result<Vec<(Publication, CategoryId)>, actix_web::Error>,
-) -> Box<
-    Future<
-        Item = Vec<Result<(Publication, CategoryId), ScannerError>>,
-        Error = actix::MailboxError,
-    >,
-> {
-    let mut batch = Vec::new();
-    let publications = result.unwrap();
-    for data in publications.into_iter() {
-        let scanner = scanner.clone();
-        let task = scanner.send(LoadMetadata {
-            config: config.clone(),
-            publication: data.0,
-            category_id: data.1,
-        });
-        batch.push(task);
-    }
-
-    Box::new(join_all(batch))
-}
-
-fn update_publication(
-    db: Addr<DbExecutor>,
-    result: Vec<Result<(Publication, CategoryId), ScannerError>>,
-) -> Box<
-    Future<
-        Item = Vec<Result<(Publication, CategoryId), ScannerError>>,
-        Error = actix::MailboxError,
-    >,
-> {
-    let mut batch = Vec::new();
-    for data in result.into_iter() {
-        let db = db.clone();
-        let data = data.unwrap();
-        let task = db
-            .send(publication::Update {
-                publication: data.0.clone(),
-            })
-            .map(|_| Ok(data));
-        batch.push(task)
-    }
-
-    Box::new(join_all(batch))
-}
-
-fn save_publication_categories(
-    db: Addr<DbExecutor>,
-    publications: Vec<Result<(Publication, CategoryId), ScannerError>>,
-) -> Box<Future<Item = Result<(), actix_web::Error>, Error = actix::MailboxError>> {
-    let publication_categories: Vec<PublicationCategory> = publications
-        .into_iter()
-        .map(|res| {
-            let (publication, category_id) = res.unwrap();
-            PublicationCategory {
-                publication_id: publication.id,
-                category_id: category_id,
-            }
-        })
-        .collect();
-    Box::new(db.send(publication_category::CreateBatch(publication_categories)))
+    process::exit(sys.run());
 }
@@ -14,11 +14,49 @@ table! {
 }
 
 table! {
-    favorite_category (category_id) {
+    category_rule (id) {
+        id -> Integer,
+        pattern -> Text,
+        kind -> Text,
+        category_id -> Integer,
+        priority -> Integer,
+    }
+}
+
+table! {
+    content_index_failure (publication_id) {
+        publication_id -> Integer,
+        file_size -> Nullable<BigInt>,
+        file_modified -> Nullable<Timestamp>,
+        timestamp -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    favorite_category (user_id, category_id) {
+        user_id -> Integer,
         category_id -> Integer,
     }
 }
 
+table! {
+    ignored_file (file_hash) {
+        file_hash -> Text,
+        file -> Text,
+        timestamp -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    library (id) {
+        id -> Integer,
+        name -> Text,
+        path -> Text,
+        media_type_id -> Integer,
+        category_id -> Nullable<Integer>,
+    }
+}
+
 table! {
     media_type (id) {
         id -> Integer,
@@ -26,6 +64,14 @@ table! {
     }
 }
 
+table! {
+    media_type_hint (id) {
+        id -> Integer,
+        folder -> Text,
+        media_type_id -> Integer,
+    }
+}
+
 table! {
     publication (id) {
         id -> Integer,
@@ -37,6 +83,14 @@ table! {
         thumbnail -> Nullable<Text>,
         file -> Text,
         timestamp -> Nullable<Timestamp>,
+        language -> Nullable<Text>,
+        series -> Nullable<Text>,
+        issue -> Nullable<Text>,
+        file_size -> Nullable<BigInt>,
+        file_modified -> Nullable<Timestamp>,
+        file_hash -> Nullable<Text>,
+        missing -> Bool,
+        thumbnail_hash -> Nullable<BigInt>,
     }
 }
 
@@ -48,7 +102,8 @@ table! {
 }
 
 table! {
-    publication_progress (publication_id) {
+    publication_progress (user_id, publication_id) {
+        user_id -> Integer,
         publication_id -> Integer,
         progress -> Float,
     }
@@ -62,16 +117,25 @@ table! {
 }
 
 table! {
-    recent_publication (publication_id) {
+    recent_publication (user_id, publication_id) {
+        user_id -> Integer,
         publication_id -> Integer,
         timestamp -> Nullable<Timestamp>,
     }
 }
 
+table! {
+    session (token) {
+        token -> Text,
+        user_id -> Integer,
+        expires_at -> Timestamp,
+    }
+}
+
 table! {
     setting (setting_id) {
         setting_id -> Integer,
-        publication_path -> Nullable<Text>,
+        min_category_score -> Double,
     }
 }
 
@@ -86,29 +150,46 @@ table! {
     user (id) {
         id -> Integer,
         username -> Text,
+        password_hash -> Text,
+        is_admin -> Bool,
     }
 }
 
+joinable!(category_rule -> category (category_id));
+joinable!(content_index_failure -> publication (publication_id));
 joinable!(favorite_category -> category (category_id));
+joinable!(favorite_category -> user (user_id));
+joinable!(library -> category (category_id));
+joinable!(library -> media_type (media_type_id));
+joinable!(media_type_hint -> media_type (media_type_id));
 joinable!(publication -> author (author_id));
 joinable!(publication -> media_type (media_type_id));
 joinable!(publication_category -> category (category_id));
 joinable!(publication_category -> publication (publication_id));
 joinable!(publication_progress -> publication (publication_id));
+joinable!(publication_progress -> user (user_id));
 joinable!(publication_tag -> publication (publication_id));
 joinable!(publication_tag -> tag (tag_id));
 joinable!(recent_publication -> publication (publication_id));
+joinable!(recent_publication -> user (user_id));
+joinable!(session -> user (user_id));
 
 allow_tables_to_appear_in_same_query!(
     author,
     category,
+    category_rule,
+    content_index_failure,
     favorite_category,
+    ignored_file,
+    library,
     media_type,
+    media_type_hint,
     publication,
     publication_category,
     publication_progress,
     publication_tag,
     recent_publication,
+    session,
     setting,
     tag,
     user,
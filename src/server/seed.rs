@@ -31,16 +31,24 @@ fn insert_favorite_category(connection: &SqliteConnection) {
         "Picture book".to_string(),
     ];
 
+    let user_ids: Vec<i32> = schema::user::table
+        .select(schema::user::id)
+        .load(connection)
+        .expect("Error loading users");
+
     for favorite_category_name in favorites {
         let category =
             get_category(&favorite_category_name, connection).expect("Error getting category");
 
-        diesel::insert_into(favorite_category)
-            .values(&FavoriteCategory {
-                category_id: category.id,
-            })
-            .execute(connection)
-            .expect("Error inserting category");
+        for the_user_id in user_ids.iter() {
+            diesel::insert_into(favorite_category)
+                .values(&FavoriteCategory {
+                    user_id: *the_user_id,
+                    category_id: category.id,
+                })
+                .execute(connection)
+                .expect("Error inserting category");
+        }
     }
 }
 
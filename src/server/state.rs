@@ -1,11 +1,24 @@
 use actix::prelude::Addr;
 use config::Config;
 use db::executor::DbExecutor;
+use fs::cache::Cache;
 use fs::executor::FsExecutor;
+use password::PasswordHasher;
+use reader::executor::ReaderExecutor;
+use scan::actor::Scanner;
+use scan::progress::Progress;
+use scan::watch::Watch;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Addr<DbExecutor>,
     pub fs: Addr<FsExecutor>,
+    pub password: Addr<PasswordHasher>,
+    pub reader: Addr<ReaderExecutor>,
+    pub scanner: Addr<Scanner>,
+    pub progress: Progress,
     pub config: Config,
+    pub cache: Cache,
+    /// Set when the server watches the library folders for changes.
+    pub watch: Option<Addr<Watch>>,
 }
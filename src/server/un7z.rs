@@ -0,0 +1,122 @@
+use reader::comic_info;
+use sevenz_rust::{self, Password, SevenZReader};
+use std::{fs, fs::File, io, io::Read, path::Component, path::Path, path::PathBuf};
+
+fn open(file: &str) -> io::Result<SevenZReader<File>> {
+    SevenZReader::open(file, Password::empty()).map_err(to_io_error)
+}
+
+fn to_io_error(err: sevenz_rust::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.to_string())
+}
+
+/// Names of the files in the archive, folders are left out.
+pub fn names(file: &str) -> io::Result<Vec<String>> {
+    let reader = open(file)?;
+    Ok(reader
+        .archive()
+        .files
+        .iter()
+        .filter(|entry| !entry.is_directory())
+        .map(|entry| entry.name().to_string())
+        .collect())
+}
+
+pub fn read_comic_info(file: &str) -> io::Result<Option<Vec<u8>>> {
+    let mut content = None;
+    let mut res = Ok(());
+    open(file)?
+        .for_each_entries(|entry, reader| {
+            if !comic_info::is_comic_info(entry.name()) {
+                return Ok(true);
+            }
+            let mut buffer = Vec::new();
+            res = reader.read_to_end(&mut buffer).map(|_| ());
+            content = Some(buffer);
+            Ok(false)
+        })
+        .map_err(to_io_error)?;
+    res.map(|_| content)
+}
+
+/// Hand every file in the archive to `read` without extracting it, in the order they are stored.
+pub fn read_files<F>(file: &str, mut read: F) -> io::Result<()>
+where
+    F: FnMut(&str, &mut Read),
+{
+    open(file)?
+        .for_each_entries(|entry, reader| {
+            if !entry.is_directory() {
+                read(entry.name(), reader);
+                // the entries share one stream, the next one starts where this one ends
+                io::copy(reader, &mut io::sink())?;
+            }
+            Ok(true)
+        })
+        .map_err(to_io_error)
+}
+
+/// Extract a single file, `name` is its path inside the archive. 7-Zip archives are usually solid,
+/// so the entries before it are decompressed on the way.
+pub fn un7z_by_name(file: &str, output_path: &str, name: &str) -> io::Result<String> {
+    let outpath = output_file(output_path, name)?;
+    let mut res = Err(io::Error::new(io::ErrorKind::NotFound, name.to_string()));
+    open(file)?
+        .for_each_entries(|entry, reader| {
+            if entry.is_directory() || entry.name() != name {
+                return Ok(true);
+            }
+            res = write_file(reader, &outpath);
+            Ok(false)
+        })
+        .map_err(to_io_error)?;
+    res?;
+    outpath
+        .to_str()
+        .map(|path| path.to_string())
+        .ok_or(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid file name",
+        ))
+}
+
+pub fn un7z(file: &str, output_path: &str) -> io::Result<()> {
+    let mut res = Ok(());
+    open(file)?
+        .for_each_entries(|entry, reader| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+            res = output_file(output_path, entry.name())
+                .and_then(|outpath| write_file(reader, &outpath));
+            Ok(res.is_ok())
+        })
+        .map_err(to_io_error)?;
+    res
+}
+
+/// Where an entry goes inside the output folder, names that would end up outside of it are
+/// refused.
+fn output_file(output_path: &str, name: &str) -> io::Result<PathBuf> {
+    let name = Path::new(name);
+    let is_safe = name.components().all(|component| match component {
+        Component::Normal(_) => true,
+        _ => false,
+    });
+    if !is_safe {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid file name {}", name.to_string_lossy()),
+        ));
+    }
+    Ok(Path::new(output_path).join(name))
+}
+
+fn write_file(reader: &mut Read, outpath: &Path) -> io::Result<()> {
+    if let Some(parent) = outpath.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut outfile = File::create(outpath)?;
+    io::copy(reader, &mut outfile)?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use reader::comic_info;
+use std::{fs::File, io, io::Read, path::Path, path::PathBuf};
+use tar::Archive;
+
+fn open(file: &str) -> io::Result<Archive<File>> {
+    Ok(Archive::new(File::open(Path::new(file))?))
+}
+
+/// Names of the regular files in the archive.
+pub fn names(file: &str) -> io::Result<Vec<String>> {
+    let mut archive = open(file)?;
+    let mut names = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.header().entry_type().is_file() {
+            names.push(entry.path()?.to_string_lossy().to_string());
+        }
+    }
+    Ok(names)
+}
+
+pub fn read_comic_info(file: &str) -> io::Result<Option<Vec<u8>>> {
+    let mut archive = open(file)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if comic_info::is_comic_info(&entry.path()?.to_string_lossy()) {
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+/// Hand every regular file in the archive to `read` without extracting it, in the order they are
+/// stored.
+pub fn read_files<F>(file: &str, mut read: F) -> io::Result<()>
+where
+    F: FnMut(&str, &mut Read),
+{
+    let mut archive = open(file)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_file() {
+            let name = entry.path()?.to_string_lossy().to_string();
+            read(&name, &mut entry);
+        }
+    }
+    Ok(())
+}
+
+/// Extract a single file, `name` is its path inside the archive.
+pub fn untar_by_name(file: &str, output_path: &str, name: &str) -> io::Result<String> {
+    let mut archive = open(file)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() != name {
+            continue;
+        }
+        // refuses paths that would end up outside of the output folder
+        if !entry.unpack_in(output_path)? {
+            break;
+        }
+        let mut outpath = PathBuf::from(output_path);
+        outpath.push(name);
+        return outpath
+            .to_str()
+            .map(|path| path.to_string())
+            .ok_or(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid file name",
+            ));
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, name.to_string()))
+}
+
+pub fn untar(file: &str, output_path: &str) -> io::Result<()> {
+    open(file)?.unpack(output_path)
+}
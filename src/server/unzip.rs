@@ -1,4 +1,5 @@
-use std::{fs, fs::File, io, path::Path, path::PathBuf};
+use reader::comic_info;
+use std::{fs, fs::File, io, io::Read, path::Path, path::PathBuf};
 use zip::result::ZipResult;
 use zip::ZipArchive;
 
@@ -14,35 +15,68 @@ pub fn unzip(file: &str, output_path_str: &str) -> ZipResult<()> {
     Ok(())
 }
 
-pub fn count(file: &str) -> ZipResult<usize> {
+/// Names of the files in the archive, folders are left out.
+pub fn names(file: &str) -> ZipResult<Vec<String>> {
     let fname = Path::new(file);
     let file = File::open(&fname)?;
-    let archive = ZipArchive::new(file)?;
-    Ok(archive.len())
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut names = Vec::new();
+    for i in 0..archive.len() {
+        if let Some(zip_file) = archive.by_index(i).ok() {
+            if !zip_file.name().ends_with('/') {
+                names.push(zip_file.name().to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+pub fn read_comic_info(file: &str) -> ZipResult<Option<Vec<u8>>> {
+    let fname = Path::new(file);
+    let file = File::open(&fname)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    for i in 0..archive.len() {
+        let mut zip_file = archive.by_index(i)?;
+        if comic_info::is_comic_info(zip_file.name()) {
+            let mut content = Vec::new();
+            zip_file.read_to_end(&mut content)?;
+            return Ok(Some(content));
+        }
+    }
+
+    Ok(None)
 }
 
-pub fn unzip_nth(file: &str, output_path: &str, nth: usize) -> ZipResult<String> {
+/// Hand every file in the archive to `read` without extracting it, in the order they are stored.
+pub fn read_files<F>(file: &str, mut read: F) -> ZipResult<()>
+where
+    F: FnMut(&str, &mut Read),
+{
     let fname = Path::new(file);
     let file = File::open(&fname)?;
     let mut archive = ZipArchive::new(file)?;
 
-    let mut files: Vec<String> = Vec::new();
     for i in 0..archive.len() {
-        if let Some(mut zip_file) = archive.by_index(i).ok() {
-            let file_name = zip_file.name();
-            files.push(file_name.to_string());
+        let mut zip_file = archive.by_index(i)?;
+        if !zip_file.name().ends_with('/') {
+            let name = zip_file.name().to_string();
+            read(&name, &mut zip_file);
         }
     }
+    Ok(())
+}
 
-    let mut files: Vec<String> = files
-        .into_iter()
-        .filter(|file| !(*file).ends_with("/"))
-        .collect();
-    files.sort();
-    let name = files.get(nth).ok_or(zip::result::ZipError::FileNotFound)?;
+/// Extract a single file, `name` is its path inside the archive.
+pub fn unzip_by_name(file: &str, output_path: &str, name: &str) -> ZipResult<String> {
+    let fname = Path::new(file);
+    let file = File::open(&fname)?;
+    let mut archive = ZipArchive::new(file)?;
 
     internal_unzip_by_name(&mut archive, output_path, name)
 }
+
 fn internal_unzip_nth(
     archive: &mut ZipArchive<File>,
     output_path: &str,
@@ -60,11 +94,11 @@ fn internal_unzip_nth(
     } else {
         if let Some(p) = outpath.parent() {
             if !p.exists() {
-                fs::create_dir_all(&p).unwrap();
+                fs::create_dir_all(&p)?;
             }
         }
-        let mut outfile = fs::File::create(&outpath).unwrap();
-        io::copy(&mut file, &mut outfile).unwrap();
+        let mut outfile = fs::File::create(&outpath)?;
+        io::copy(&mut file, &mut outfile)?;
     }
 
     // Get and Set permissions
@@ -73,7 +107,7 @@ fn internal_unzip_nth(
         use std::os::unix::fs::PermissionsExt;
 
         if let Some(mode) = file.unix_mode() {
-            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).unwrap();
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
         }
     }
 
@@ -100,11 +134,11 @@ fn internal_unzip_by_name(
     } else {
         if let Some(p) = outpath.parent() {
             if !p.exists() {
-                fs::create_dir_all(&p).unwrap();
+                fs::create_dir_all(&p)?;
             }
         }
-        let mut outfile = fs::File::create(&outpath).unwrap();
-        io::copy(&mut file, &mut outfile).unwrap();
+        let mut outfile = fs::File::create(&outpath)?;
+        io::copy(&mut file, &mut outfile)?;
     }
 
     // Get and Set permissions
@@ -113,7 +147,7 @@ fn internal_unzip_by_name(
         use std::os::unix::fs::PermissionsExt;
 
         if let Some(mode) = file.unix_mode() {
-            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).unwrap();
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
         }
     }
 